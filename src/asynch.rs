@@ -0,0 +1,76 @@
+//! Async variant of the driver, built on [embedded-hal-async](https://crates.io/crates/embedded-hal-async).
+//!
+//! Enabled with the `async` feature. [VL6180X] uses the same
+//! operating modes as the blocking driver, but every bus access is awaited and the
+//! blocking reads yield to the executor between polls of the sensor.
+//!
+//! ```ignore
+//! let mut tof = vl6180x::asynch::VL6180X::new(i2c, delay).await.expect("vl");
+//! let range = tof.poll_range_mm_single().await;
+//! ```
+use crate::Config;
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, digital::Wait, i2c::I2c};
+
+#[cfg(test)]
+mod asynch_tests;
+
+mod calibration;
+mod device_status;
+mod i2c_interface;
+mod init;
+mod interrupt_pin;
+mod mode;
+mod read_measurements;
+mod start_stop_measurements;
+
+/// Time waited between two reads of the interrupt status register while waiting
+/// for a measurement result.
+const POLL_INTERVAL_US: u32 = 1000;
+
+/// Async VL6180 interface
+#[derive(Debug, Clone, Copy)]
+pub struct VL6180X<MODE, I2C: I2c, D: DelayNs> {
+    mode: MODE,
+    com: I2C,
+    delay: D,
+    config: Config,
+}
+
+/// Convenience container for the async VL6180, x_shutdown_pin and interrupt_pin
+#[derive(Debug, Clone, Copy)]
+pub struct VL6180XwPins<MODE, I2C: I2c, D: DelayNs, OP: OutputPin, IP: Wait> {
+    /// VL6180
+    pub vl6180x: VL6180X<MODE, I2C, D>,
+    /// X Shutdown Pin, output high => powered on, output low => powered off.
+    pub x_shutdown_pin: OP,
+    /// Interrupt pin for receiving interrupts from the sensor.
+    pub interrupt_pin: IP,
+}
+
+impl<MODE, I2C, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    fn into_mode<MODE2>(self, mode: MODE2) -> VL6180X<MODE2, I2C, D> {
+        VL6180X {
+            mode,
+            com: self.com,
+            delay: self.delay,
+            config: self.config,
+        }
+    }
+
+    /// Release the I2C bus, e.g. to hand it to another driver after
+    /// [power_off](VL6180X::power_off).
+    /// The driver can be rebuilt with [new_powered_off](VL6180X::new_powered_off).
+    pub fn release(self) -> I2C {
+        self.com
+    }
+
+    /// Release the I2C bus and the delay.
+    pub fn release_with_delay(self) -> (I2C, D) {
+        (self.com, self.delay)
+    }
+}
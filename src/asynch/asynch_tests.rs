@@ -0,0 +1,142 @@
+use super::*;
+use crate::{
+    register::{Register16Bit::*, Register8Bit::*, AMBIENT_ANALOGUE_GAIN_CODE, RANGE_SCALAR_CODE},
+    sim::SimBus,
+    Error, RangeStatusErrorCode,
+};
+use embassy_futures::block_on;
+
+#[test]
+fn with_config_writes_configuration() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_max_convergence_time(30).unwrap();
+    config.set_range_result_scaler(2).unwrap();
+    config.set_ambient_analogue_gain_level(3).unwrap();
+    block_on(async {
+        VL6180X::with_config(bus.i2c(), bus.delay(), &config)
+            .await
+            .unwrap();
+    });
+
+    assert_eq!(sensor.register(SYSTEM__FRESH_OUT_OF_RESET as u16), 0);
+    assert_eq!(sensor.register(SYSRANGE__MAX_CONVERGENCE_TIME as u16), 30);
+    assert_eq!(
+        sensor.register_16bit(RANGE_SCALER as u16),
+        RANGE_SCALAR_CODE[2]
+    );
+    assert_eq!(
+        sensor.register(SYSALS__ANALOGUE_GAIN as u16),
+        AMBIENT_ANALOGUE_GAIN_CODE[3]
+    );
+}
+
+#[test]
+fn poll_single_measurements() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(60);
+    sensor.set_ambient_counts(1000);
+    block_on(async {
+        let mut tof = VL6180X::new(bus.i2c(), bus.delay()).await.unwrap();
+        assert_eq!(tof.read_model_id().await, Ok(0xB4));
+        assert_eq!(tof.poll_range_mm_single().await, Ok(60));
+        // 0.32 * 100 / 1.01 * 1000 / 100
+        let lux = tof.poll_ambient_lux_single().await.unwrap();
+        assert!((lux - 316.8).abs() < 0.1);
+    });
+}
+
+#[test]
+fn read_if_ready_before_measurement() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(60);
+    sensor.set_measurement_time_us(5_000);
+    block_on(async {
+        let mut tof = VL6180X::new(bus.i2c(), bus.delay()).await.unwrap();
+        tof.start_range_single().await.unwrap();
+        assert_eq!(
+            tof.read_range_mm_if_ready().await,
+            Err(Error::ResultNotReady)
+        );
+        assert_eq!(tof.read_range_mm().await, Ok(60));
+    });
+    assert!(bus.now_us() >= 5_000);
+}
+
+#[test]
+fn continuous_modes() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(42);
+    sensor.set_ambient_counts(500);
+    block_on(async {
+        let tof = VL6180X::new(bus.i2c(), bus.delay()).await.unwrap();
+
+        let mut tof = tof.start_range_continuous_mode().await.unwrap();
+        assert_eq!(tof.read_range_mm().await, Ok(42));
+        sensor.set_range_mm(43);
+        assert_eq!(tof.read_range_mm().await, Ok(43));
+        let tof = tof.stop_range_continuous_mode().await.unwrap();
+
+        let mut tof = tof.start_ambient_continuous_mode().await.unwrap();
+        assert_eq!(tof.read_ambient().await, Ok(500));
+        let tof = tof.stop_ambient_continuous_mode().await.unwrap();
+
+        let mut tof = tof.start_interleaved_continuous_mode().await.unwrap();
+        assert_eq!(tof.read_ambient().await, Ok(500));
+        assert_eq!(tof.read_range_mm().await, Ok(43));
+        tof.stop_interleaved_continuous_mode().await.unwrap();
+    });
+}
+
+#[test]
+fn change_i2c_address() {
+    let (bus, _sensor) = SimBus::with_sensor();
+    block_on(async {
+        let mut tof = VL6180X::new(bus.i2c(), bus.delay()).await.unwrap();
+        tof.change_i2c_address(0x30).await.unwrap();
+        assert_eq!(tof.read_model_id().await, Ok(0xB4));
+    });
+}
+
+#[test]
+fn calibrate_range_offset_error_restores_offset() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_register(SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16, 3);
+    block_on(async {
+        let mut tof = VL6180X::new(bus.i2c(), bus.delay()).await.unwrap();
+        sensor.set_range_status(Some(RangeStatusErrorCode::MaxConvergence));
+        assert_eq!(
+            tof.calibrate_range_offset(50, 10).await,
+            Err(Error::RangeStatusError(
+                RangeStatusErrorCode::MaxConvergence
+            ))
+        );
+    });
+    assert_eq!(
+        sensor.register(SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16),
+        3
+    );
+}
+
+#[test]
+fn calibrate_crosstalk_error_restores_compensation() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_crosstalk_compensation_rate(50);
+    block_on(async {
+        let mut tof = VL6180X::with_config(bus.i2c(), bus.delay(), &config)
+            .await
+            .unwrap();
+        sensor.set_range_status(Some(RangeStatusErrorCode::MaxConvergence));
+        assert_eq!(
+            tof.calibrate_crosstalk(100, 10).await,
+            Err(Error::RangeStatusError(
+                RangeStatusErrorCode::MaxConvergence
+            ))
+        );
+    });
+    assert_eq!(
+        sensor.register_16bit(SYSRANGE__CROSSTALK_COMPENSATION_RATE as u16),
+        50
+    );
+}
@@ -0,0 +1,129 @@
+use super::VL6180X;
+use crate::{
+    calibration::{
+        check_calibration_args, check_lux_calibration_args, crosstalk_compensation_rate,
+        lux_resolution_factor, range_offset_mm,
+    },
+    error::Error,
+    register::{Register16Bit, Register8Bit},
+};
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    pub(super) async fn calibrate_range_offset_direct(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<i8, Error<E>> {
+        check_calibration_args(target_distance_mm, samples)?;
+
+        // Measurements must be taken without any offset applied
+        let previous_offset = self
+            .read_named_register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET)
+            .await?;
+        self.write_named_register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET, 0)
+            .await?;
+
+        let mut range_sum: u32 = 0;
+        for _ in 0..samples {
+            match self.poll_range_mm_single_direct().await {
+                Ok(range) => range_sum += range as u32,
+                Err(error) => {
+                    // Leave the offset as it was, the measurement error is the one to report
+                    let _ = self
+                        .write_named_register(
+                            Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET,
+                            previous_offset,
+                        )
+                        .await;
+                    return Err(error);
+                }
+            }
+        }
+        let offset = range_offset_mm(target_distance_mm, samples, range_sum);
+
+        self.write_named_register(
+            Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET,
+            (offset / self.config.range_scaling as i8) as u8,
+        )
+        .await?;
+        self.config.ptp_offset = offset;
+        self.config.range_offset_mm = Some(offset);
+
+        Ok(offset)
+    }
+
+    pub(super) async fn calibrate_crosstalk_direct(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<u16, Error<E>> {
+        check_calibration_args(target_distance_mm, samples)?;
+
+        // Measurements must be taken without any compensation applied
+        let previous_rate = self
+            .read_named_register_16bit(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE)
+            .await?;
+        self.write_named_register_16bit(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE, 0)
+            .await?;
+
+        let (range_sum, return_rate_sum) = match self.sum_crosstalk_samples_direct(samples).await {
+            Ok(sums) => sums,
+            Err(error) => {
+                // Leave the compensation as it was, the measurement error is the one to report
+                let _ = self
+                    .write_named_register_16bit(
+                        Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE,
+                        previous_rate,
+                    )
+                    .await;
+                return Err(error);
+            }
+        };
+        let rate =
+            crosstalk_compensation_rate(target_distance_mm, samples, range_sum, return_rate_sum);
+
+        self.write_named_register_16bit(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE, rate)
+            .await?;
+        self.config.range_crosstalk_compensation_rate = rate;
+
+        Ok(rate)
+    }
+
+    async fn sum_crosstalk_samples_direct(&mut self, samples: u8) -> Result<(u32, u32), Error<E>> {
+        let mut range_sum: u32 = 0;
+        let mut return_rate_sum: u32 = 0;
+        for _ in 0..samples {
+            range_sum += self.poll_range_mm_single_direct().await? as u32;
+            return_rate_sum += self
+                .read_named_register_16bit(Register16Bit::RESULT__RANGE_RETURN_RATE)
+                .await? as u32;
+        }
+        Ok((range_sum, return_rate_sum))
+    }
+
+    pub(super) async fn calibrate_ambient_lux_direct(
+        &mut self,
+        reference_lux: f32,
+        samples: u8,
+    ) -> Result<f32, Error<E>> {
+        check_lux_calibration_args(reference_lux, samples)?;
+
+        let mut ambient_sum: u32 = 0;
+        for _ in 0..samples {
+            ambient_sum += self.poll_ambient_single_direct().await? as u32;
+        }
+        let factor = lux_resolution_factor(&self.config, reference_lux, samples, ambient_sum)?;
+
+        // Interrupt thresholds set in lux change with the factor
+        let mut config = self.config;
+        config.ambient_lux_resolution_factor = factor;
+        self.write_changed_registers(&config).await?;
+
+        Ok(factor)
+    }
+}
@@ -0,0 +1,104 @@
+use super::VL6180X;
+use crate::{
+    device_status::{
+        parse_device_info, DeviceInfo, BOOT_POLL_INTERVAL_US, BOOT_TIME_US, DEVICE_INFO_LEN,
+    },
+    error::{Error, Error2},
+    register::{Register8Bit::*, SysHistoryCtrlCode, SysInterruptClearCode},
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    pub(super) async fn read_model_id_direct(&mut self) -> Result<u8, Error<E>> {
+        let id = self.read_named_register(IDENTIFICATION__MODEL_ID).await?;
+        Ok(id)
+    }
+
+    pub(super) async fn read_device_info_direct(&mut self) -> Result<DeviceInfo, Error<E>> {
+        let mut info = [0u8; DEVICE_INFO_LEN];
+        self.read_bytes(IDENTIFICATION__MODEL_ID as u16, &mut info)
+            .await?;
+        Ok(parse_device_info(&info))
+    }
+
+    pub(super) async fn read_interrupt_status_direct(&mut self) -> Result<u8, Error<E>> {
+        let status = self
+            .read_named_register(RESULT__INTERRUPT_STATUS_GPIO)
+            .await?;
+        Ok(status)
+    }
+
+    pub(super) async fn clear_history_buffer_direct(&mut self) -> Result<(), Error<E>> {
+        self.write_named_register(
+            SYSTEM__HISTORY_CTRL,
+            self.config.history_buffer_mode as u8 | SysHistoryCtrlCode::Clear as u8,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub(super) async fn clear_interrupt(&mut self, code: u8) -> Result<(), E> {
+        self.write_named_register(SYSTEM__INTERRUPT_CLEAR, code)
+            .await
+    }
+
+    pub(super) async fn clear_range_interrupt_direct(&mut self) -> Result<(), Error<E>> {
+        self.clear_interrupt(SysInterruptClearCode::Range as u8)
+            .await?;
+        Ok(())
+    }
+
+    pub(super) async fn clear_ambient_interrupt_direct(&mut self) -> Result<(), Error<E>> {
+        self.clear_interrupt(SysInterruptClearCode::Ambient as u8)
+            .await?;
+        Ok(())
+    }
+
+    pub(super) async fn change_i2c_address_direct(
+        &mut self,
+        new_address: u8,
+    ) -> Result<(), Error<E>> {
+        if !(0x08..=0x77).contains(&new_address) {
+            return Err(Error::InvalidAddress(new_address));
+        }
+        self.write_named_register(I2C_SLAVE__DEVICE_ADDRESS, new_address)
+            .await?;
+        self.config.address = new_address;
+
+        Ok(())
+    }
+
+    pub(super) async fn power_on_and_init_direct<PE, P: OutputPin<Error = PE>>(
+        &mut self,
+        x_shutdown_pin: &mut P,
+    ) -> Result<(), Error2<E, PE>> {
+        x_shutdown_pin.set_high().map_err(Error2::GpioPinError)?;
+        self.wait_device_booted().await?;
+        self.init_hardware()
+            .await
+            .map_err(Error2::<E, PE>::BusError)?;
+        Ok(())
+    }
+
+    async fn wait_device_booted(&mut self) -> Result<(), Error<E>> {
+        self.delay.delay_us(BOOT_TIME_US).await;
+        let mut waited_us: u32 = 0;
+        loop {
+            let last_error = match self.read_named_register(SYSTEM__FRESH_OUT_OF_RESET).await {
+                Ok(0x01) => return Ok(()),
+                Ok(_) => None,
+                Err(e) => Some(e),
+            };
+            if waited_us >= self.config.boot_timeout_us {
+                return Err(Error::BootTimeout(last_error));
+            }
+            self.delay.delay_us(BOOT_POLL_INTERVAL_US).await;
+            waited_us = waited_us.saturating_add(BOOT_POLL_INTERVAL_US);
+        }
+    }
+}
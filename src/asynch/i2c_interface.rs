@@ -0,0 +1,60 @@
+use super::VL6180X;
+use crate::register::{Register16Bit, Register8Bit};
+use embedded_hal_async::{delay::DelayNs, i2c::I2c, i2c::Operation};
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Reads a named 8-bit register
+    pub(super) async fn read_named_register(&mut self, reg: Register8Bit) -> Result<u8, E> {
+        let mut data: [u8; 1] = [0];
+        self.read_bytes(reg as u16, &mut data).await?;
+        Ok(data[0])
+    }
+
+    /// Reads a named 16-bit register
+    pub(super) async fn read_named_register_16bit(&mut self, reg: Register16Bit) -> Result<u16, E> {
+        let mut data: [u8; 2] = [0, 0];
+        self.read_bytes(reg as u16, &mut data).await?;
+        Ok(u16::from_be_bytes(data))
+    }
+
+    /// Reads `data.len()` consecutive bytes starting at `reg`
+    pub(super) async fn read_bytes(&mut self, reg: u16, data: &mut [u8]) -> Result<(), E> {
+        let reg: [u8; 2] = reg.to_be_bytes();
+        self.com.write_read(self.config.address, &reg, data).await
+    }
+
+    pub(super) async fn write_named_register(
+        &mut self,
+        reg: Register8Bit,
+        code: u8,
+    ) -> Result<(), E> {
+        self.write_register(reg as u16, code).await
+    }
+
+    pub(super) async fn write_register(&mut self, reg: u16, code: u8) -> Result<(), E> {
+        self.write_bytes(reg, &[code]).await
+    }
+
+    pub(super) async fn write_named_register_16bit(
+        &mut self,
+        reg: Register16Bit,
+        code: u16,
+    ) -> Result<(), E> {
+        self.write_bytes(reg as u16, &code.to_be_bytes()).await
+    }
+
+    /// Writes `data` to consecutive registers starting at `reg` in a single transaction
+    pub(super) async fn write_bytes(&mut self, reg: u16, data: &[u8]) -> Result<(), E> {
+        let reg: [u8; 2] = reg.to_be_bytes();
+        self.com
+            .transaction(
+                self.config.address,
+                &mut [Operation::Write(&reg), Operation::Write(data)],
+            )
+            .await
+    }
+}
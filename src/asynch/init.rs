@@ -0,0 +1,153 @@
+use super::VL6180X;
+use crate::init::{config_registers, ConfigRegister, SR03_SETTINGS};
+use crate::register::{Register16Bit::*, Register8Bit::*, RANGE_SCALAR_CODE};
+use crate::{error::Error, Config};
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Async counterpart of the blocking `init_hardware`, writes the same registers
+    /// in the same order.
+    pub(super) async fn init_hardware(&mut self) -> Result<(), E> {
+        // Store part-to-part range offset so it can be adjusted if scaling is changed,
+        // unless a calibrated offset has been configured
+        self.config.ptp_offset = match self.config.range_offset_mm {
+            Some(offset) => offset,
+            None => {
+                self.read_named_register(SYSRANGE__PART_TO_PART_RANGE_OFFSET)
+                    .await? as i8
+            }
+        };
+
+        for (reg, code) in SR03_SETTINGS.iter() {
+            self.write_register(*reg, *code).await?;
+        }
+
+        self.write_named_register(SYSTEM__FRESH_OUT_OF_RESET, 0)
+            .await?;
+
+        self.set_configuration().await?;
+
+        Ok(())
+    }
+
+    async fn set_configuration(&mut self) -> Result<(), E> {
+        for register in config_registers(&self.config).iter() {
+            self.write_config_register(*register).await?;
+        }
+
+        // Manually trigger a range VHV recalibration
+        self.write_named_register(SYSRANGE__VHV_RECALIBRATE, 0x01)
+            .await?;
+
+        // disable interleaved mode
+        self.write_named_register(INTERLEAVED_MODE__ENABLE, 0)
+            .await?;
+
+        self.set_range_scaling(self.config.range_scaling).await?;
+
+        Ok(())
+    }
+
+    async fn write_config_register(&mut self, register: ConfigRegister) -> Result<(), E> {
+        match register {
+            ConfigRegister::Byte(reg, code) => self.write_register(reg, code).await,
+            ConfigRegister::Word(reg, code) => self.write_bytes(reg, &code.to_be_bytes()).await,
+        }
+    }
+
+    /// Async counterpart of the blocking `write_changed_registers`.
+    pub(super) async fn write_changed_registers(&mut self, config: &Config) -> Result<(), E> {
+        let old = config_registers(&self.config);
+        let new = config_registers(config);
+        let changed = || old.iter().zip(new.iter()).filter(|(o, n)| o != n);
+
+        if changed().any(|(_, n)| n.is_grouped()) {
+            self.write_named_register(SYSTEM__GROUPED_PARAMETER_HOLD, 0x01)
+                .await?;
+            for (_, register) in changed().filter(|(_, n)| n.is_grouped()) {
+                self.write_config_register(*register).await?;
+            }
+            self.write_named_register(SYSTEM__GROUPED_PARAMETER_HOLD, 0x00)
+                .await?;
+        }
+        for (_, register) in changed().filter(|(_, n)| !n.is_grouped()) {
+            self.write_config_register(*register).await?;
+        }
+
+        let scaling_changed = config.range_scaling != self.config.range_scaling
+            || config.ptp_offset != self.config.ptp_offset;
+        let address = self.config.address;
+        self.config = *config;
+        self.config.address = address;
+        if scaling_changed {
+            self.set_range_scaling(self.config.range_scaling).await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn apply_config_direct(&mut self, config: &Config) -> Result<(), Error<E>> {
+        config.validate()?;
+        let mut config = *config;
+        config.ptp_offset = config.range_offset_mm.unwrap_or(self.config.ptp_offset);
+        self.write_changed_registers(&config).await?;
+        if config.address != self.config.address {
+            self.change_i2c_address_direct(config.address).await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn apply_interrupt_thresholds_direct(
+        &mut self,
+        config: &Config,
+    ) -> Result<(), E> {
+        let mut new_config = self.config;
+        new_config.copy_interrupt_thresholds(config);
+        self.write_changed_registers(&new_config).await
+    }
+
+    pub(super) async fn set_range_thresholds_mm_direct(
+        &mut self,
+        low_mm: u16,
+        high_mm: u16,
+    ) -> Result<(), Error<E>> {
+        let config = self.config.with_range_thresholds_mm(low_mm, high_mm)?;
+        Ok(self.write_changed_registers(&config).await?)
+    }
+
+    pub(super) async fn set_ambient_thresholds_direct(
+        &mut self,
+        low: u16,
+        high: u16,
+    ) -> Result<(), Error<E>> {
+        let config = self.config.with_ambient_thresholds(low, high)?;
+        Ok(self.write_changed_registers(&config).await?)
+    }
+
+    async fn set_range_scaling(&mut self, new_scaling: u8) -> Result<(), E> {
+        const DEFAULT_CROSSTALK_VALID_HEIGHT: u8 = 20; // default value of SYSRANGE__CROSSTALK_VALID_HEIGHT
+
+        let scaling = new_scaling;
+        self.write_named_register_16bit(RANGE_SCALER, RANGE_SCALAR_CODE[scaling as usize])
+            .await?;
+
+        // apply scaling on part-to-part offset
+        self.write_named_register(
+            SYSRANGE__PART_TO_PART_RANGE_OFFSET,
+            (self.config.ptp_offset / scaling as i8) as u8,
+        )
+        .await?;
+
+        // apply scaling on CrossTalkValidHeight
+        self.write_named_register(
+            SYSRANGE__CROSSTALK_VALID_HEIGHT,
+            DEFAULT_CROSSTALK_VALID_HEIGHT / scaling,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
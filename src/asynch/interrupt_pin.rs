@@ -0,0 +1,155 @@
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+
+use super::{VL6180XwPins, POLL_INTERVAL_US};
+use crate::{
+    error::{Error, Error2},
+    mode::{AllowReadMeasurement, ReadyMode},
+    read_measurements::{
+        ambient_read_timeout_us, convert_raw_ambient_to_lux, range_read_timeout_us,
+    },
+    register::ResultInterruptStatusGpioCode,
+    AmbientInterruptMode, GpioPolarity, RangeInterruptMode,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, digital::Wait, i2c::I2c};
+
+impl<MODE, I2C, E, D, OP, IP, PE> VL6180XwPins<MODE, I2C, D, OP, IP>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+    OP: OutputPin,
+    IP: Wait<Error = PE>,
+    MODE: AllowReadMeasurement,
+{
+    /// Read of the range measurement, awaiting the `interrupt_pin`
+    /// instead of polling the sensor over I2C.
+    /// The reading (whether single or continuous) must already have been started and the
+    /// [range_interrupt_mode](crate::config::Config::set_range_interrupt_mode) must not be disabled.
+    /// The `interrupt_pin` is the GPIO configured as interrupt output, GPIO1 by default, see
+    /// [set_gpio1_function](crate::config::Config::set_gpio1_function).
+    ///
+    /// Returns [Error::Timeout] if the `interrupt_pin` is not asserted in time.
+    ///
+    /// Note: in interleaved mode the interrupt is shared with the ambient light measurement.
+    pub async fn read_range_mm_interrupt(&mut self) -> Result<u16, Error2<E, PE>> {
+        if let RangeInterruptMode::Disabled = self.vl6180x.config.range_interrupt_mode {
+            return Err(Error2::DriverError(Error::InvalidConfigurationValue(
+                RangeInterruptMode::Disabled as u16,
+            )));
+        }
+        let timeout_us = range_read_timeout_us(&self.vl6180x.config, MODE::INTERLEAVED);
+        self.wait_interrupt(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)
+            .await?;
+        Ok(self.vl6180x.get_range_val_and_status().await?)
+    }
+
+    /// Read of the ambient light measurement, awaiting the `interrupt_pin`
+    /// instead of polling the sensor over I2C.
+    /// The reading (whether single or continuous) must already have been started and the
+    /// [ambient_interrupt_mode](crate::config::Config::set_ambient_interrupt_mode) must not be disabled.
+    /// The `interrupt_pin` is the GPIO configured as interrupt output, GPIO1 by default, see
+    /// [set_gpio1_function](crate::config::Config::set_gpio1_function).
+    ///
+    /// Returns [Error::Timeout] if the `interrupt_pin` is not asserted in time.
+    ///
+    /// Note: in interleaved mode the interrupt is shared with the range measurement.
+    pub async fn read_ambient_lux_interrupt(&mut self) -> Result<f32, Error2<E, PE>> {
+        if let AmbientInterruptMode::Disabled = self.vl6180x.config.ambient_interrupt_mode {
+            return Err(Error2::DriverError(Error::InvalidConfigurationValue(
+                AmbientInterruptMode::Disabled as u16,
+            )));
+        }
+        let timeout_us = ambient_read_timeout_us(&self.vl6180x.config, MODE::INTERLEAVED);
+        self.wait_interrupt(ResultInterruptStatusGpioCode::NoAmbientEvents, timeout_us)
+            .await?;
+        let raw_ambient = self.vl6180x.get_ambient_val_and_status().await?;
+        Ok(convert_raw_ambient_to_lux(
+            &self.vl6180x.config,
+            raw_ambient,
+        ))
+    }
+
+    /// Waits until `look_for` is no longer reported in the interrupt status, first on the
+    /// interrupt line, then polling the status in case another event asserted the line.
+    /// Returns [Error::Timeout] if the event is not reported within `timeout_us`.
+    async fn wait_interrupt(
+        &mut self,
+        look_for: ResultInterruptStatusGpioCode,
+        timeout_us: u32,
+    ) -> Result<(), Error2<E, PE>> {
+        let waited_us = self.wait_interrupt_pin(timeout_us).await?;
+        // The line is shared by the range and ambient light events
+        self.vl6180x
+            .wait_for_event(look_for, timeout_us.saturating_sub(waited_us))
+            .await?;
+        Ok(())
+    }
+
+    /// Waits until the interrupt line is asserted, with the polarity of the GPIO
+    /// configured as interrupt output, and returns the time waited.
+    /// Returns [Error::Timeout] if the line is not asserted within `timeout_us`.
+    async fn wait_interrupt_pin(&mut self, timeout_us: u32) -> Result<u32, Error2<E, PE>> {
+        let polarity = match self.vl6180x.config.interrupt_polarity() {
+            Some(polarity) => polarity,
+            None => return Err(Error2::DriverError(Error::NoInterruptOutput)),
+        };
+        let mut waited_us: u32 = 0;
+        loop {
+            let interrupt_pin = &mut self.interrupt_pin;
+            let asserted = async {
+                match polarity {
+                    GpioPolarity::ActiveHigh => interrupt_pin.wait_for_high().await,
+                    GpioPolarity::ActiveLow => interrupt_pin.wait_for_low().await,
+                }
+            };
+            let interval = self.vl6180x.delay.delay_us(POLL_INTERVAL_US);
+            if let Some(result) = with_timeout(asserted, interval).await {
+                return result.map(|()| waited_us).map_err(Error2::GpioPinError);
+            }
+            waited_us = waited_us.saturating_add(POLL_INTERVAL_US);
+            if waited_us >= timeout_us {
+                return Err(Error2::DriverError(Error::Timeout));
+            }
+        }
+    }
+}
+
+/// Awaits `future`, or gives `None` if `timeout` completes first.
+async fn with_timeout<F: Future>(
+    future: F,
+    timeout: impl Future<Output = ()>,
+) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let mut timeout = pin!(timeout);
+    poll_fn(|cx| match future.as_mut().poll(cx) {
+        Poll::Ready(output) => Poll::Ready(Some(output)),
+        Poll::Pending => timeout.as_mut().poll(cx).map(|()| None),
+    })
+    .await
+}
+
+impl<I2C, E, D, OP, IP, PE> VL6180XwPins<ReadyMode, I2C, D, OP, IP>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+    OP: OutputPin,
+    IP: Wait<Error = PE>,
+{
+    /// Starts a single range measurement then awaits
+    /// [`read_range_mm_interrupt`](VL6180XwPins::read_range_mm_interrupt).
+    pub async fn poll_range_mm_single_interrupt(&mut self) -> Result<u16, Error2<E, PE>> {
+        self.vl6180x.start_range_single_direct().await?;
+        self.read_range_mm_interrupt().await
+    }
+
+    /// Starts a single ambient light measurement then awaits
+    /// [`read_ambient_lux_interrupt`](VL6180XwPins::read_ambient_lux_interrupt).
+    pub async fn poll_ambient_lux_single_interrupt(&mut self) -> Result<f32, Error2<E, PE>> {
+        self.vl6180x.start_ambient_single_direct().await?;
+        self.read_ambient_lux_interrupt().await
+    }
+}
@@ -0,0 +1,446 @@
+use super::VL6180X;
+use crate::{
+    error::{Error, Error2},
+    mode::{
+        AllowCommunication, AllowReadMeasurement, AllowStartAmbientSingle, AllowStartRangeSingle,
+        AmbientContinuousMode, InterleavedContinuousMode, PoweredOffMode, RangeContinuousMode,
+        ReadyMode,
+    },
+    register::{SysInterruptClearCode, AMBIENT_HISTORY_LEN, RANGE_HISTORY_LEN},
+    AmbientMeasurement, Config, DeviceInfo, RangeMeasurement, ScaledRangeMeasurement,
+};
+use embedded_hal::digital::OutputPin;
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+    MODE: AllowReadMeasurement,
+{
+    /// Read of the range measurement, yielding between polls until the result is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    pub async fn read_range_mm(&mut self) -> Result<u16, Error<E>> {
+        self.read_range_mm_blocking_direct(MODE::INTERLEAVED).await
+    }
+
+    /// Read of the range measurement if it is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    /// Returns [Error::ResultNotReady] if the result is not ready.
+    pub async fn read_range_mm_if_ready(&mut self) -> Result<u16, Error<E>> {
+        self.read_range_mm_direct().await
+    }
+
+    /// Read of the range measurement together with its signal diagnostics, yielding between
+    /// polls until the result is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    ///
+    /// A measurement with an error status is returned rather than turned into an error, see
+    /// [RangeMeasurement::status].
+    pub async fn read_range_detailed(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        self.read_range_detailed_blocking_direct(MODE::INTERLEAVED)
+            .await
+    }
+
+    /// Read of the range measurement together with its signal diagnostics if it is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    /// Returns [Error::ResultNotReady] if the result is not ready.
+    pub async fn read_range_detailed_if_ready(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        self.read_range_detailed_direct().await
+    }
+
+    /// Read the range history buffer in a single transfer, most recent measurement first.
+    ///
+    /// The [history_buffer_mode](crate::config::Config::set_history_buffer_mode) must be
+    /// `Range`, otherwise returns [Error::InvalidConfigurationValue]. The buffer only holds
+    /// range values, the status of each measurement is not kept. Slots that have not been
+    /// filled since the buffer was last cleared read as 0.
+    pub async fn read_range_history_mm(&mut self) -> Result<[u16; RANGE_HISTORY_LEN], Error<E>> {
+        self.read_range_history_mm_direct().await
+    }
+
+    /// Read the raw ambient light history buffer in a single transfer, most recent measurement first.
+    ///
+    /// The [history_buffer_mode](crate::config::Config::set_history_buffer_mode) must be
+    /// `Ambient`, otherwise returns [Error::InvalidConfigurationValue]. Slots that have not been
+    /// filled since the buffer was last cleared read as 0.
+    pub async fn read_ambient_history(&mut self) -> Result<[u16; AMBIENT_HISTORY_LEN], Error<E>> {
+        self.read_ambient_history_direct().await
+    }
+
+    /// Read of the ambient light measurement, yielding between polls until the result is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    pub async fn read_ambient_lux(&mut self) -> Result<f32, Error<E>> {
+        self.read_ambient_lux_blocking_direct(MODE::INTERLEAVED)
+            .await
+    }
+
+    /// Read of the ambient light measurement if it is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    /// Returns [Error::ResultNotReady] if the result is not ready.
+    pub async fn read_ambient_lux_if_ready(&mut self) -> Result<f32, Error<E>> {
+        self.read_ambient_lux_direct().await
+    }
+
+    /// Read of the raw ambient light measurement, yielding between polls until the result is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    pub async fn read_ambient(&mut self) -> Result<u16, Error<E>> {
+        self.read_ambient_blocking_direct(MODE::INTERLEAVED).await
+    }
+
+    /// Read of the raw ambient light measurement if it is ready.
+    /// The reading (whether single or continuous) must already have been started.
+    /// Returns [Error::ResultNotReady] if the result is not ready.
+    pub async fn read_ambient_if_ready(&mut self) -> Result<u16, Error<E>> {
+        self.read_ambient_direct().await
+    }
+}
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+    MODE: AllowStartAmbientSingle,
+{
+    /// Trigger ambient light measurement without waiting for the result.
+    /// Await [`read_ambient_lux`](VL6180X::read_ambient_lux) or
+    /// [`read_ambient`](VL6180X::read_ambient) to get the measured value.
+    pub async fn start_ambient_single(&mut self) -> Result<(), Error<E>> {
+        self.start_ambient_single_direct().await?;
+        Ok(())
+    }
+}
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+    MODE: AllowStartRangeSingle,
+{
+    /// Trigger range measurement without waiting for the result.
+    /// Await [`read_range_mm`](VL6180X::read_range_mm) to get the measured value.
+    pub async fn start_range_single(&mut self) -> Result<(), Error<E>> {
+        self.start_range_single_direct().await?;
+        Ok(())
+    }
+}
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+    MODE: AllowCommunication,
+{
+    /// Read the model id of the sensor. Should return 0xB4.
+    pub async fn read_model_id(&mut self) -> Result<u8, Error<E>> {
+        self.read_model_id_direct().await
+    }
+
+    /// Read the identification and revision of the sensor.
+    /// See [`read_device_info`](crate::VL6180X::read_device_info) of the blocking driver.
+    pub async fn read_device_info(&mut self) -> Result<DeviceInfo, Error<E>> {
+        self.read_device_info_direct().await
+    }
+
+    /// Read the current interrupt status of the sensor.
+    /// See [`read_interrupt_status`](crate::VL6180X::read_interrupt_status) of the blocking driver.
+    pub async fn read_interrupt_status(&mut self) -> Result<u8, Error<E>> {
+        self.read_interrupt_status_direct().await
+    }
+
+    /// Clear error interrupt
+    pub async fn clear_error_interrupt(&mut self) -> Result<(), Error<E>> {
+        self.clear_interrupt(SysInterruptClearCode::Error as u8)
+            .await?;
+        Ok(())
+    }
+
+    /// Clear ambient interrupt
+    pub async fn clear_ambient_interrupt(&mut self) -> Result<(), Error<E>> {
+        self.clear_ambient_interrupt_direct().await
+    }
+
+    /// Clear range interrupt
+    pub async fn clear_range_interrupt(&mut self) -> Result<(), Error<E>> {
+        self.clear_range_interrupt_direct().await
+    }
+
+    /// Clear the history buffer, see
+    /// [history_buffer_mode](crate::config::Config::set_history_buffer_mode).
+    pub async fn clear_history_buffer(&mut self) -> Result<(), Error<E>> {
+        self.clear_history_buffer_direct().await
+    }
+
+    /// Clear all interrupts (error, ambient and range)
+    pub async fn clear_all_interrupts(&mut self) -> Result<(), Error<E>> {
+        self.clear_interrupt(
+            SysInterruptClearCode::Range as u8
+                | SysInterruptClearCode::Ambient as u8
+                | SysInterruptClearCode::Error as u8,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Powers off the sensor by setting the `x_shutdown_pin` low.
+    pub fn power_off<PE, P: OutputPin<Error = PE>>(
+        self,
+        x_shutdown_pin: &mut P,
+    ) -> Result<VL6180X<PoweredOffMode, I2C, D>, Error<PE>> {
+        x_shutdown_pin.set_low().map_err(Error::GpioPinError)?;
+        Ok(self.into_mode(PoweredOffMode {}))
+    }
+
+    /// Change current i2c address to new i2c address.
+    /// See [`change_i2c_address`](crate::VL6180X::change_i2c_address) of the blocking driver.
+    pub async fn change_i2c_address(&mut self, new_address: u8) -> Result<(), Error<E>> {
+        self.change_i2c_address_direct(new_address).await
+    }
+
+    /// Update the range and ambient interrupt thresholds to those of `config`.
+    /// See [`apply_interrupt_thresholds`](crate::VL6180X::apply_interrupt_thresholds) of the
+    /// blocking driver.
+    pub async fn apply_interrupt_thresholds(&mut self, config: &Config) -> Result<(), Error<E>> {
+        Ok(self.apply_interrupt_thresholds_direct(config).await?)
+    }
+
+    /// Set the range interrupt thresholds (mm) within a grouped parameter hold.
+    /// See [`set_range_thresholds_mm`](crate::VL6180X::set_range_thresholds_mm) of the
+    /// blocking driver.
+    pub async fn set_range_thresholds_mm(
+        &mut self,
+        low_mm: u16,
+        high_mm: u16,
+    ) -> Result<(), Error<E>> {
+        self.set_range_thresholds_mm_direct(low_mm, high_mm).await
+    }
+
+    /// Set the ambient light interrupt thresholds, in raw counts, within a grouped
+    /// parameter hold.
+    /// See [`set_ambient_thresholds`](crate::VL6180X::set_ambient_thresholds) of the
+    /// blocking driver.
+    pub async fn set_ambient_thresholds(&mut self, low: u16, high: u16) -> Result<(), Error<E>> {
+        self.set_ambient_thresholds_direct(low, high).await
+    }
+}
+
+impl<I2C, E, D> VL6180X<ReadyMode, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Create a new async VL6180X driver
+    pub async fn new(i2c: I2C, delay: D) -> Result<Self, Error<E>> {
+        VL6180X::with_config(i2c, delay, &Config::new()).await
+    }
+
+    /// Create a new async VL6180X driver cloning provided config values
+    pub async fn with_config(i2c: I2C, delay: D, config: &Config) -> Result<Self, Error<E>> {
+        let mut chip = Self {
+            mode: ReadyMode,
+            com: i2c,
+            delay,
+            config: *config,
+        };
+        let chip_id = chip.read_model_id_direct().await?;
+        if chip_id == 0xB4 {
+            chip.init_hardware().await?;
+            Ok(chip)
+        } else {
+            Err(Error::InvalidDevice(chip_id))
+        }
+    }
+
+    /// Poll the sensor for a single range measurement.
+    /// Starts a single range measurement then awaits [`read_range_mm`](VL6180X::read_range_mm).
+    pub async fn poll_range_mm_single(&mut self) -> Result<u16, Error<E>> {
+        self.poll_range_mm_single_direct().await
+    }
+
+    /// Poll the sensor for a single range measurement, adjusting the range scaling to the
+    /// distance. See the blocking
+    /// [poll_range_mm_single_auto_scaling_blocking](crate::VL6180X::poll_range_mm_single_auto_scaling_blocking).
+    pub async fn poll_range_mm_single_auto_scaling(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        self.poll_range_mm_single_auto_scaling_direct().await
+    }
+
+    /// Poll the sensor for a single range measurement together with its signal diagnostics.
+    /// Starts a single range measurement then awaits
+    /// [`read_range_detailed`](VL6180X::read_range_detailed).
+    pub async fn poll_range_detailed_single(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        self.poll_range_detailed_single_direct().await
+    }
+
+    /// Poll the sensor for a single ambient light measurement.
+    /// Starts a single ambient measurement then awaits [`read_ambient_lux`](VL6180X::read_ambient_lux).
+    pub async fn poll_ambient_lux_single(&mut self) -> Result<f32, Error<E>> {
+        self.poll_ambient_lux_single_direct().await
+    }
+
+    /// Poll the sensor for a single ambient light measurement, adjusting the analogue gain
+    /// to the light level. See the blocking
+    /// [poll_ambient_lux_single_auto_gain_blocking](crate::VL6180X::poll_ambient_lux_single_auto_gain_blocking).
+    pub async fn poll_ambient_lux_single_auto_gain(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        self.poll_ambient_lux_single_auto_gain_direct().await
+    }
+
+    /// Calibrate the part-to-part range offset.
+    /// See the blocking [calibrate_range_offset](crate::VL6180X::calibrate_range_offset).
+    pub async fn calibrate_range_offset(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<i8, Error<E>> {
+        self.calibrate_range_offset_direct(target_distance_mm, samples)
+            .await
+    }
+
+    /// Calibrate the range crosstalk compensation for a cover window.
+    /// See the blocking [calibrate_crosstalk](crate::VL6180X::calibrate_crosstalk).
+    pub async fn calibrate_crosstalk(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<u16, Error<E>> {
+        self.calibrate_crosstalk_direct(target_distance_mm, samples)
+            .await
+    }
+
+    /// Calibrate the conversion of ambient light measurements to lux.
+    /// See the blocking [calibrate_ambient_lux](crate::VL6180X::calibrate_ambient_lux).
+    pub async fn calibrate_ambient_lux(
+        &mut self,
+        reference_lux: f32,
+        samples: u8,
+    ) -> Result<f32, Error<E>> {
+        self.calibrate_ambient_lux_direct(reference_lux, samples)
+            .await
+    }
+
+    /// Reconfigure the sensor with `config` without a power cycle.
+    /// See [`apply_config`](crate::VL6180X::apply_config) of the blocking driver.
+    pub async fn apply_config(&mut self, config: &Config) -> Result<(), Error<E>> {
+        self.apply_config_direct(config).await
+    }
+
+    /// Starts continuous operation mode for reading range measurements.
+    pub async fn start_range_continuous_mode(
+        self,
+    ) -> Result<VL6180X<RangeContinuousMode, I2C, D>, Error<E>> {
+        let mut new_vl6180x = self.into_mode(RangeContinuousMode {});
+        new_vl6180x.toggle_range_continuous_direct().await?;
+        Ok(new_vl6180x)
+    }
+
+    /// Starts continuous operation mode for reading ambient light measurements.
+    pub async fn start_ambient_continuous_mode(
+        self,
+    ) -> Result<VL6180X<AmbientContinuousMode, I2C, D>, Error<E>> {
+        let mut new_vl6180x = self.into_mode(AmbientContinuousMode {});
+        new_vl6180x.toggle_ambient_continuous_direct().await?;
+        Ok(new_vl6180x)
+    }
+
+    /// Starts continuous operation mode for interleaved ambient light and range measurements.
+    /// The intermeasurement period is set by the [`ambient_inter_measurement_period`](crate::config::Config::set_ambient_inter_measurement_period)
+    pub async fn start_interleaved_continuous_mode(
+        self,
+    ) -> Result<VL6180X<InterleavedContinuousMode, I2C, D>, Error<E>> {
+        let mut new_vl6180x = self.into_mode(InterleavedContinuousMode {});
+        new_vl6180x.enable_interleaved_continuous_direct().await?;
+        Ok(new_vl6180x)
+    }
+}
+
+impl<I2C, E, D> VL6180X<RangeContinuousMode, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Stops range continuous mode.
+    pub async fn stop_range_continuous_mode(
+        mut self,
+    ) -> Result<VL6180X<ReadyMode, I2C, D>, Error<E>> {
+        self.toggle_range_continuous_direct().await?;
+        Ok(self.into_mode(ReadyMode {}))
+    }
+
+    /// Read the next range measurement, adjusting the range scaling to the distance. See
+    /// the blocking
+    /// [read_range_mm_auto_scaling_blocking](crate::VL6180X::read_range_mm_auto_scaling_blocking).
+    pub async fn read_range_mm_auto_scaling(&mut self) -> Result<ScaledRangeMeasurement, Error<E>> {
+        self.read_range_mm_auto_scaling_direct().await
+    }
+}
+
+impl<I2C, E, D> VL6180X<AmbientContinuousMode, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Stops ambient continuous mode.
+    pub async fn stop_ambient_continuous_mode(
+        mut self,
+    ) -> Result<VL6180X<ReadyMode, I2C, D>, Error<E>> {
+        self.toggle_ambient_continuous_direct().await?;
+        Ok(self.into_mode(ReadyMode {}))
+    }
+
+    /// Read the next ambient light measurement, adjusting the analogue gain to the light
+    /// level. See the blocking
+    /// [read_ambient_lux_auto_gain_blocking](crate::VL6180X::read_ambient_lux_auto_gain_blocking).
+    pub async fn read_ambient_lux_auto_gain(&mut self) -> Result<AmbientMeasurement, Error<E>> {
+        self.read_ambient_lux_auto_gain_direct().await
+    }
+}
+
+impl<I2C, E, D> VL6180X<InterleavedContinuousMode, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Stops interleaved continuous mode.
+    pub async fn stop_interleaved_continuous_mode(
+        mut self,
+    ) -> Result<VL6180X<ReadyMode, I2C, D>, Error<E>> {
+        self.stop_interleaved_continuous_direct().await?;
+        Ok(self.into_mode(ReadyMode {}))
+    }
+}
+
+impl<I2C, E, D> VL6180X<PoweredOffMode, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Create an async driver for a sensor that is powered off.
+    ///
+    /// Does not communicate with the sensor, so a driver can be rebuilt from a
+    /// [released](VL6180X::release) bus and a saved `config`.
+    /// The sensor is initialized with `config` by [power_on_and_init](VL6180X::power_on_and_init).
+    pub fn new_powered_off(i2c: I2C, delay: D, config: &Config) -> Self {
+        Self {
+            mode: PoweredOffMode {},
+            com: i2c,
+            delay,
+            config: *config,
+        }
+    }
+
+    /// Powers on the sensor by setting the `x_shutdown_pin` high.
+    /// It then waits for the device to be booted and initializes the device.
+    ///
+    /// Returns [Error::BootTimeout] if the device has not booted
+    /// within the [boot timeout](crate::Config::set_boot_timeout_us).
+    pub async fn power_on_and_init<PE, P: OutputPin<Error = PE>>(
+        mut self,
+        x_shutdown_pin: &mut P,
+    ) -> Result<VL6180X<ReadyMode, I2C, D>, Error2<E, PE>> {
+        self.power_on_and_init_direct(x_shutdown_pin).await?;
+        Ok(self.into_mode(ReadyMode))
+    }
+}
@@ -0,0 +1,243 @@
+use core::convert::TryFrom;
+
+use super::{POLL_INTERVAL_US, VL6180X};
+use crate::{
+    error::Error,
+    read_measurements::{
+        ambient_measurement, ambient_read_timeout_us, auto_gain_level, auto_range_scaling,
+        check_history_buffer_mode, convert_raw_ambient_to_lux, convert_raw_range_to_mm,
+        discard_measurement, parse_ambient_history, parse_range_measurement, range_read_timeout_us,
+        ScaledRangeMeasurement, AUTO_GAIN_ATTEMPTS, AUTO_SCALING_ATTEMPTS, RANGE_RESULTS_LEN,
+    },
+    register::{
+        AmbientStatusErrorCode, RangeStatusErrorCode, Register16Bit, Register8Bit,
+        ResultInterruptStatusGpioCode, AMBIENT_HISTORY_LEN, RANGE_HISTORY_LEN,
+    },
+    AmbientMeasurement, HistoryBufferMode, RangeMeasurement,
+};
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Waits until `look_for` is no longer reported in the interrupt status,
+    /// yielding to the executor between each poll until `timeout_us` has passed.
+    pub(super) async fn wait_for_event(
+        &mut self,
+        look_for: ResultInterruptStatusGpioCode,
+        timeout_us: u32,
+    ) -> Result<(), Error<E>> {
+        let mut waited_us: u32 = 0;
+        while ResultInterruptStatusGpioCode::has_status(
+            look_for,
+            self.read_named_register(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO)
+                .await?,
+        ) {
+            if waited_us >= timeout_us {
+                return Err(Error::Timeout);
+            }
+            self.delay.delay_us(POLL_INTERVAL_US).await;
+            waited_us = waited_us.saturating_add(POLL_INTERVAL_US);
+        }
+        Ok(())
+    }
+
+    pub(super) async fn read_range_mm_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<u16, Error<E>> {
+        let timeout_us = range_read_timeout_us(&self.config, interleaved);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)
+            .await?;
+        self.get_range_val_and_status().await
+    }
+
+    pub(super) async fn read_range_mm_direct(&mut self) -> Result<u16, Error<E>> {
+        if ResultInterruptStatusGpioCode::has_status(
+            ResultInterruptStatusGpioCode::NoRangeEvents,
+            self.read_named_register(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO)
+                .await?,
+        ) {
+            return Err(Error::ResultNotReady);
+        }
+        self.get_range_val_and_status().await
+    }
+
+    pub(super) async fn get_range_val_and_status(&mut self) -> Result<u16, Error<E>> {
+        let status = self
+            .read_named_register(Register8Bit::RESULT__RANGE_STATUS)
+            .await?;
+        self.clear_range_interrupt_direct().await?;
+        let error = RangeStatusErrorCode::try_from(status)
+            .map_err(|_| Error::UnknownRegisterCode(status))?;
+        if error != RangeStatusErrorCode::NoError {
+            return Err(Error::RangeStatusError(error));
+        }
+        let raw_range = self
+            .read_named_register(Register8Bit::RESULT__RANGE_VAL)
+            .await?;
+        Ok(convert_raw_range_to_mm(&self.config, raw_range))
+    }
+
+    pub(super) async fn read_range_detailed_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<RangeMeasurement, Error<E>> {
+        let timeout_us = range_read_timeout_us(&self.config, interleaved);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)
+            .await?;
+        self.get_range_detailed().await
+    }
+
+    pub(super) async fn read_range_detailed_direct(
+        &mut self,
+    ) -> Result<RangeMeasurement, Error<E>> {
+        if ResultInterruptStatusGpioCode::has_status(
+            ResultInterruptStatusGpioCode::NoRangeEvents,
+            self.read_named_register(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO)
+                .await?,
+        ) {
+            return Err(Error::ResultNotReady);
+        }
+        self.get_range_detailed().await
+    }
+
+    pub(super) async fn get_range_detailed(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        let status = self
+            .read_named_register(Register8Bit::RESULT__RANGE_STATUS)
+            .await?;
+        let mut results = [0u8; RANGE_RESULTS_LEN];
+        self.read_bytes(Register8Bit::RESULT__RANGE_VAL as u16, &mut results)
+            .await?;
+        self.clear_range_interrupt_direct().await?;
+        parse_range_measurement(&self.config, status, &results)
+    }
+
+    pub(super) async fn read_range_history_mm_direct(
+        &mut self,
+    ) -> Result<[u16; RANGE_HISTORY_LEN], Error<E>> {
+        check_history_buffer_mode(&self.config, HistoryBufferMode::Range)?;
+        let mut history = [0u8; RANGE_HISTORY_LEN];
+        self.read_bytes(Register16Bit::RESULT__HISTORY_BUFFER_0 as u16, &mut history)
+            .await?;
+        Ok(history.map(|raw_range| convert_raw_range_to_mm(&self.config, raw_range)))
+    }
+
+    pub(super) async fn read_ambient_history_direct(
+        &mut self,
+    ) -> Result<[u16; AMBIENT_HISTORY_LEN], Error<E>> {
+        check_history_buffer_mode(&self.config, HistoryBufferMode::Ambient)?;
+        let mut history = [0u8; AMBIENT_HISTORY_LEN * 2];
+        self.read_bytes(Register16Bit::RESULT__HISTORY_BUFFER_0 as u16, &mut history)
+            .await?;
+        Ok(parse_ambient_history(&history))
+    }
+
+    pub(super) async fn read_ambient_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<u16, Error<E>> {
+        let timeout_us = ambient_read_timeout_us(&self.config, interleaved);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoAmbientEvents, timeout_us)
+            .await?;
+        self.get_ambient_val_and_status().await
+    }
+
+    pub(super) async fn read_ambient_direct(&mut self) -> Result<u16, Error<E>> {
+        if ResultInterruptStatusGpioCode::has_status(
+            ResultInterruptStatusGpioCode::NoAmbientEvents,
+            self.read_named_register(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO)
+                .await?,
+        ) {
+            return Err(Error::ResultNotReady);
+        }
+        self.get_ambient_val_and_status().await
+    }
+
+    pub(super) async fn read_ambient_lux_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<f32, Error<E>> {
+        let raw_ambient = self.read_ambient_blocking_direct(interleaved).await?;
+        Ok(convert_raw_ambient_to_lux(&self.config, raw_ambient))
+    }
+
+    pub(super) async fn read_ambient_lux_direct(&mut self) -> Result<f32, Error<E>> {
+        let raw_ambient = self.read_ambient_direct().await?;
+        Ok(convert_raw_ambient_to_lux(&self.config, raw_ambient))
+    }
+
+    pub(super) async fn read_range_mm_auto_scaling_direct(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.read_range_mm_blocking_direct(false).await;
+            match auto_range_scaling(&self.config, &result) {
+                Some(scaling) if attempts < AUTO_SCALING_ATTEMPTS => {
+                    self.set_range_scaling_direct(scaling).await?;
+                    // The sample in progress was started with the previous scaling
+                    discard_measurement(self.read_range_mm_blocking_direct(false).await)?;
+                    attempts += 1;
+                }
+                _ => {
+                    return Ok(ScaledRangeMeasurement {
+                        range_mm: result?,
+                        scaling: self.config.range_scaling,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Changes the range scaling, re-applying the range offset, the crosstalk valid height
+    /// and the range settings given in mm.
+    pub(super) async fn set_range_scaling_direct(&mut self, scaling: u8) -> Result<(), E> {
+        let mut config = self.config;
+        config.range_scaling = scaling;
+        self.write_changed_registers(&config).await
+    }
+
+    pub(super) async fn read_ambient_lux_auto_gain_direct(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.read_ambient_blocking_direct(false).await;
+            match auto_gain_level(self.config.ambient_analogue_gain_level, &result) {
+                Some(level) if attempts < AUTO_GAIN_ATTEMPTS => {
+                    self.set_ambient_gain_level_direct(level).await?;
+                    // The sample in progress was started with the previous gain
+                    discard_measurement(self.read_ambient_blocking_direct(false).await)?;
+                    attempts += 1;
+                }
+                _ => return Ok(ambient_measurement(&self.config, result?)),
+            }
+        }
+    }
+
+    /// Changes the analogue gain level, rewriting the ambient thresholds set in lux.
+    pub(super) async fn set_ambient_gain_level_direct(&mut self, level: u8) -> Result<(), E> {
+        let mut config = self.config;
+        config.ambient_analogue_gain_level = level;
+        self.write_changed_registers(&config).await
+    }
+
+    pub(super) async fn get_ambient_val_and_status(&mut self) -> Result<u16, Error<E>> {
+        let status = self
+            .read_named_register(Register8Bit::RESULT__ALS_STATUS)
+            .await?;
+        self.clear_ambient_interrupt_direct().await?;
+        let error = AmbientStatusErrorCode::try_from(status)
+            .map_err(|_| Error::UnknownRegisterCode(status))?;
+        if error != AmbientStatusErrorCode::NoError {
+            return Err(Error::AmbientStatusError(error));
+        }
+        let raw_ambient = self
+            .read_named_register_16bit(Register16Bit::RESULT__ALS_VAL)
+            .await?;
+        Ok(raw_ambient)
+    }
+}
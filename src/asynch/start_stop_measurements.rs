@@ -0,0 +1,146 @@
+use super::VL6180X;
+use crate::{
+    error::Error,
+    read_measurements::{
+        ambient_measurement, ambient_timeout_us, auto_gain_level, auto_range_scaling,
+        convert_raw_ambient_to_lux, range_timeout_us, AmbientMeasurement, ScaledRangeMeasurement,
+        AUTO_GAIN_ATTEMPTS, AUTO_SCALING_ATTEMPTS,
+    },
+    register::{
+        InterleavedModeEnableCode, Register8Bit, ResultInterruptStatusGpioCode,
+        SysAmbientStartCode, SysRangeStartCode,
+    },
+    start_stop_measurements::check_interleaved_config_valid,
+    RangeMeasurement,
+};
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+impl<MODE, I2C, E, D> VL6180X<MODE, I2C, D>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    pub(super) async fn poll_range_mm_single_direct(&mut self) -> Result<u16, Error<E>> {
+        self.start_range_single_direct().await?;
+        let timeout_us = range_timeout_us(&self.config);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)
+            .await?;
+        self.get_range_val_and_status().await
+    }
+
+    pub(super) async fn poll_range_mm_single_auto_scaling_direct(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.poll_range_mm_single_direct().await;
+            match auto_range_scaling(&self.config, &result) {
+                Some(scaling) if attempts < AUTO_SCALING_ATTEMPTS => {
+                    self.set_range_scaling_direct(scaling).await?;
+                    attempts += 1;
+                }
+                _ => {
+                    return Ok(ScaledRangeMeasurement {
+                        range_mm: result?,
+                        scaling: self.config.range_scaling,
+                    })
+                }
+            }
+        }
+    }
+
+    pub(super) async fn poll_range_detailed_single_direct(
+        &mut self,
+    ) -> Result<RangeMeasurement, Error<E>> {
+        self.start_range_single_direct().await?;
+        let timeout_us = range_timeout_us(&self.config);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)
+            .await?;
+        self.get_range_detailed().await
+    }
+
+    pub(super) async fn poll_ambient_lux_single_direct(&mut self) -> Result<f32, Error<E>> {
+        let raw_ambient = self.poll_ambient_single_direct().await?;
+        Ok(convert_raw_ambient_to_lux(&self.config, raw_ambient))
+    }
+
+    pub(super) async fn poll_ambient_lux_single_auto_gain_direct(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.poll_ambient_single_direct().await;
+            match auto_gain_level(self.config.ambient_analogue_gain_level, &result) {
+                Some(level) if attempts < AUTO_GAIN_ATTEMPTS => {
+                    self.set_ambient_gain_level_direct(level).await?;
+                    attempts += 1;
+                }
+                _ => return Ok(ambient_measurement(&self.config, result?)),
+            }
+        }
+    }
+
+    pub(super) async fn poll_ambient_single_direct(&mut self) -> Result<u16, Error<E>> {
+        self.start_ambient_single_direct().await?;
+        let timeout_us = ambient_timeout_us(&self.config);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoAmbientEvents, timeout_us)
+            .await?;
+        self.get_ambient_val_and_status().await
+    }
+
+    pub(super) async fn start_range_single_direct(&mut self) -> Result<(), E> {
+        self.write_named_register(
+            Register8Bit::SYSRANGE__START,
+            SysRangeStartCode::SingleStart as u8,
+        )
+        .await
+    }
+
+    pub(super) async fn start_ambient_single_direct(&mut self) -> Result<(), E> {
+        self.write_named_register(
+            Register8Bit::SYSALS__START,
+            SysAmbientStartCode::SingleStart as u8,
+        )
+        .await
+    }
+
+    pub(super) async fn toggle_range_continuous_direct(&mut self) -> Result<(), E> {
+        self.write_named_register(
+            Register8Bit::SYSRANGE__START,
+            SysRangeStartCode::ContinuousStartOrStop as u8,
+        )
+        .await
+    }
+
+    pub(super) async fn toggle_ambient_continuous_direct(&mut self) -> Result<(), E> {
+        self.write_named_register(
+            Register8Bit::SYSALS__START,
+            SysAmbientStartCode::ContinuousStartOrStop as u8,
+        )
+        .await
+    }
+
+    pub(super) async fn enable_interleaved_continuous_direct(&mut self) -> Result<(), Error<E>> {
+        check_interleaved_config_valid(&self.config)?;
+
+        self.write_named_register(
+            Register8Bit::INTERLEAVED_MODE__ENABLE,
+            InterleavedModeEnableCode::Enable as u8,
+        )
+        .await?;
+        self.write_named_register(
+            Register8Bit::SYSALS__START,
+            SysAmbientStartCode::ContinuousStartOrStop as u8,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub(super) async fn stop_interleaved_continuous_direct(&mut self) -> Result<(), E> {
+        self.write_named_register(
+            Register8Bit::INTERLEAVED_MODE__ENABLE,
+            InterleavedModeEnableCode::Disable as u8,
+        )
+        .await
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    error::Error,
+    read_measurements::convert_raw_ambient_to_lux,
+    register::{Register16Bit, Register8Bit},
+    Config, WaitStrategy, VL6180X,
+};
+use embedded_hal::i2c::I2c;
+
+#[cfg(test)]
+mod calibration_tests;
+
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
+where
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
+{
+    /// Offset calibration from ST application note AN4545, section "Offset calibration".
+    pub(crate) fn calibrate_range_offset_direct(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<i8, Error<E>> {
+        check_calibration_args(target_distance_mm, samples)?;
+
+        // Measurements must be taken without any offset applied
+        let previous_offset =
+            self.read_named_register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET)?;
+        self.write_named_register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET, 0)?;
+
+        let mut range_sum: u32 = 0;
+        for _ in 0..samples {
+            match self.poll_range_mm_single_blocking_direct() {
+                Ok(range) => range_sum += range as u32,
+                Err(error) => {
+                    // Leave the offset as it was, the measurement error is the one to report
+                    let _ = self.write_named_register(
+                        Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET,
+                        previous_offset,
+                    );
+                    return Err(error);
+                }
+            }
+        }
+        let offset = range_offset_mm(target_distance_mm, samples, range_sum);
+
+        self.write_named_register(
+            Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET,
+            (offset / self.config.range_scaling as i8) as u8,
+        )?;
+        self.config.ptp_offset = offset;
+        self.config.range_offset_mm = Some(offset);
+
+        Ok(offset)
+    }
+
+    /// Crosstalk calibration from ST application note AN4545, section "Crosstalk calibration".
+    pub(crate) fn calibrate_crosstalk_direct(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<u16, Error<E>> {
+        check_calibration_args(target_distance_mm, samples)?;
+
+        // Measurements must be taken without any compensation applied
+        let previous_rate =
+            self.read_named_register_16bit(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE)?;
+        self.write_named_register_16bit(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE, 0)?;
+
+        let (range_sum, return_rate_sum) = match self.sum_crosstalk_samples_direct(samples) {
+            Ok(sums) => sums,
+            Err(error) => {
+                // Leave the compensation as it was, the measurement error is the one to report
+                let _ = self.write_named_register_16bit(
+                    Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE,
+                    previous_rate,
+                );
+                return Err(error);
+            }
+        };
+        let rate =
+            crosstalk_compensation_rate(target_distance_mm, samples, range_sum, return_rate_sum);
+
+        self.write_named_register_16bit(
+            Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE,
+            rate,
+        )?;
+        self.config.range_crosstalk_compensation_rate = rate;
+
+        Ok(rate)
+    }
+
+    /// Sums the range and the return rate of `samples` single range measurements.
+    fn sum_crosstalk_samples_direct(&mut self, samples: u8) -> Result<(u32, u32), Error<E>> {
+        let mut range_sum: u32 = 0;
+        let mut return_rate_sum: u32 = 0;
+        for _ in 0..samples {
+            range_sum += self.poll_range_mm_single_blocking_direct()? as u32;
+            return_rate_sum +=
+                self.read_named_register_16bit(Register16Bit::RESULT__RANGE_RETURN_RATE)? as u32;
+        }
+        Ok((range_sum, return_rate_sum))
+    }
+
+    /// Lux calibration, scales the lux resolution factor so the average of the
+    /// ambient light measurements reads `reference_lux`.
+    pub(crate) fn calibrate_ambient_lux_direct(
+        &mut self,
+        reference_lux: f32,
+        samples: u8,
+    ) -> Result<f32, Error<E>> {
+        check_lux_calibration_args(reference_lux, samples)?;
+
+        let mut ambient_sum: u32 = 0;
+        for _ in 0..samples {
+            ambient_sum += self.poll_ambient_single_blocking_direct()? as u32;
+        }
+        let factor = lux_resolution_factor(&self.config, reference_lux, samples, ambient_sum)?;
+
+        // Interrupt thresholds set in lux change with the factor
+        let mut config = self.config;
+        config.ambient_lux_resolution_factor = factor;
+        self.write_changed_registers(&config)?;
+
+        Ok(factor)
+    }
+}
+
+pub(crate) fn check_calibration_args<E>(
+    target_distance_mm: u16,
+    samples: u8,
+) -> Result<(), Error<E>> {
+    if target_distance_mm == 0 {
+        return Err(Error::InvalidConfigurationValue(target_distance_mm));
+    }
+    if samples == 0 {
+        return Err(Error::InvalidConfigurationValue(samples as u16));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_lux_calibration_args<E>(
+    reference_lux: f32,
+    samples: u8,
+) -> Result<(), Error<E>> {
+    if !(reference_lux > 0.0 && reference_lux.is_finite()) {
+        return Err(Error::InvalidConfigurationValue(reference_lux as u16));
+    }
+    if samples == 0 {
+        return Err(Error::InvalidConfigurationValue(samples as u16));
+    }
+    Ok(())
+}
+
+/// factor = reference_lux / lux measured with a factor of 1, using the average ambient value.
+///
+/// Returns [Error::InvalidConfigurationValue] if no light was measured.
+pub(crate) fn lux_resolution_factor<E>(
+    config: &Config,
+    reference_lux: f32,
+    samples: u8,
+    ambient_sum: u32,
+) -> Result<f32, Error<E>> {
+    if ambient_sum == 0 {
+        return Err(Error::InvalidConfigurationValue(0));
+    }
+    let mut unit_config = *config;
+    unit_config.ambient_lux_resolution_factor = 1.0;
+    let average_ambient = ambient_sum as f32 / samples as f32;
+    let unit_lux = convert_raw_ambient_to_lux(&unit_config, 1) * average_ambient;
+    Ok(reference_lux / unit_lux)
+}
+
+/// offset = target_distance - average range, rounded and saturated to the register range.
+pub(crate) fn range_offset_mm(target_distance_mm: u16, samples: u8, range_sum: u32) -> i8 {
+    let samples = samples as u32;
+    let average_range = (range_sum + samples / 2) / samples;
+    let offset = target_distance_mm as i32 - average_range as i32;
+    offset.clamp(i8::MIN as i32, i8::MAX as i32) as i8
+}
+
+/// crosstalk = return_rate * (1 - range / target_distance), averaged over the samples.
+///
+/// The return rate and the compensation rate are both in 9.7 fixed point Mcps.
+pub(crate) fn crosstalk_compensation_rate(
+    target_distance_mm: u16,
+    samples: u8,
+    range_sum: u32,
+    return_rate_sum: u32,
+) -> u16 {
+    let average_range = range_sum as f32 / samples as f32;
+    let average_return_rate = return_rate_sum as f32 / samples as f32;
+    let rate = average_return_rate * (1.0 - average_range / target_distance_mm as f32);
+    if rate > 0.0 {
+        rate as u16
+    } else {
+        0
+    }
+}
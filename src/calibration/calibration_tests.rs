@@ -0,0 +1,251 @@
+use super::*;
+use crate::{sim::SimBus, Config, RangeStatusErrorCode};
+
+/// 3 Mcps from the target and 1 Mcps of crosstalk make 100mm read 75mm.
+fn cover_window_bus() -> (SimBus, crate::sim::SimSensor) {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(100);
+    sensor.set_signal_rate(3 << 7);
+    sensor.set_crosstalk_rate(1 << 7);
+    (bus, sensor)
+}
+
+#[test]
+fn calibrate_crosstalk_compensates_cover_window() {
+    let (bus, sensor) = cover_window_bus();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(75));
+
+    assert_eq!(tof.calibrate_crosstalk(100, 10), Ok(1 << 7));
+    assert_eq!(
+        sensor.register_16bit(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE as u16),
+        1 << 7
+    );
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(100));
+}
+
+#[test]
+fn calibrate_crosstalk_ignores_previous_compensation() {
+    let (bus, _sensor) = cover_window_bus();
+    let mut config = Config::new();
+    config.set_range_crosstalk_compensation_rate(50);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(tof.calibrate_crosstalk(100, 4), Ok(1 << 7));
+}
+
+#[test]
+fn calibrate_crosstalk_error_restores_compensation() {
+    let (bus, sensor) = cover_window_bus();
+    let mut config = Config::new();
+    config.set_range_crosstalk_compensation_rate(50);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    sensor.set_range_status(Some(RangeStatusErrorCode::MaxConvergence));
+
+    assert_eq!(
+        tof.calibrate_crosstalk(100, 10),
+        Err(Error::RangeStatusError(
+            RangeStatusErrorCode::MaxConvergence
+        ))
+    );
+    assert_eq!(
+        sensor.register_16bit(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE as u16),
+        50
+    );
+    assert_eq!(tof.config.range_crosstalk_compensation_rate, 50);
+}
+
+#[test]
+fn calibrate_crosstalk_without_cover_window() {
+    let (bus, _sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(tof.calibrate_crosstalk(100, 10), Ok(0));
+}
+
+#[test]
+fn calibrate_crosstalk_invalid_samples() {
+    let (bus, _sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(
+        tof.calibrate_crosstalk(100, 0),
+        Err(Error::InvalidConfigurationValue(0))
+    );
+}
+
+#[test]
+fn crosstalk_compensation_rate_from_config() {
+    let (bus, sensor) = cover_window_bus();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let mut config = Config::new();
+    config.set_range_crosstalk_compensation_rate(1 << 7);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(100));
+
+    // The driver config is reapplied after a power cycle
+    let tof = tof.power_off(&mut x_shutdown_pin).unwrap();
+    let mut tof = tof
+        .power_on_and_init(&mut x_shutdown_pin, &mut bus.delay())
+        .unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(100));
+}
+
+#[test]
+fn calibrate_range_offset_corrects_part_error() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(50);
+    sensor.set_range_error_mm(7);
+    sensor.set_register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16, 3);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(60));
+
+    assert_eq!(tof.calibrate_range_offset(50, 10), Ok(-7));
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16),
+        -7i8 as u8
+    );
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(50));
+}
+
+#[test]
+fn calibrate_range_offset_error_restores_offset() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16, 3);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    sensor.set_range_status(Some(RangeStatusErrorCode::MaxConvergence));
+
+    assert_eq!(
+        tof.calibrate_range_offset(50, 10),
+        Err(Error::RangeStatusError(
+            RangeStatusErrorCode::MaxConvergence
+        ))
+    );
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16),
+        3
+    );
+    assert_eq!(tof.config.range_offset_mm, None);
+}
+
+#[test]
+fn calibrated_range_offset_survives_power_cycle() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    sensor.set_range_mm(50);
+    sensor.set_range_error_mm(-4);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(tof.calibrate_range_offset(50, 10), Ok(4));
+
+    let tof = tof.power_off(&mut x_shutdown_pin).unwrap();
+    let mut tof = tof
+        .power_on_and_init(&mut x_shutdown_pin, &mut bus.delay())
+        .unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(50));
+}
+
+#[test]
+fn negative_factory_range_offset_is_scaled() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_register(
+        Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16,
+        -6i8 as u8,
+    );
+    let mut config = Config::new();
+    config.set_range_result_scaler(3).unwrap();
+    VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16),
+        -2i8 as u8
+    );
+}
+
+#[test]
+fn range_offset_from_config_is_scaled() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(50);
+    sensor.set_range_error_mm(7);
+    let mut config = Config::new();
+    config.set_range_offset_mm(-7);
+    config.set_range_result_scaler(2).unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16),
+        -3i8 as u8
+    );
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(50));
+}
+
+#[test]
+fn range_offset_saturates() {
+    assert_eq!(range_offset_mm(50, 2, 2 * 250), i8::MIN);
+    assert_eq!(range_offset_mm(200, 2, 0), i8::MAX);
+    assert_eq!(range_offset_mm(50, 4, 4 * 47 + 2), 2);
+}
+
+/// Behind a tinted window 1000 counts read 316.8 lux, a quarter of the real illuminance.
+#[test]
+fn calibrate_ambient_lux_for_tinted_window() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_counts(1000);
+    let mut config = Config::new();
+    config
+        .set_ambient_low_interrupt_threshold_lux(50.0)
+        .unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    let lux = tof.poll_ambient_lux_single_blocking().unwrap();
+    assert!((lux - 316.8).abs() < 0.1);
+    assert_eq!(
+        sensor.register_16bit(Register16Bit::SYSALS__THRESH_LOW as u16),
+        158
+    );
+
+    let factor = tof.calibrate_ambient_lux(4.0 * lux, 8).unwrap();
+    assert!((factor - 1.28).abs() < 0.001);
+    let lux = tof.poll_ambient_lux_single_blocking().unwrap();
+    assert!((lux - 1267.2).abs() < 0.5);
+    assert_eq!(
+        sensor.register_16bit(Register16Bit::SYSALS__THRESH_LOW as u16),
+        39
+    );
+}
+
+#[test]
+fn calibrate_ambient_lux_in_the_dark() {
+    let (bus, _sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(
+        tof.calibrate_ambient_lux(100.0, 4),
+        Err(Error::InvalidConfigurationValue(0))
+    );
+    assert_eq!(
+        tof.calibrate_ambient_lux(-1.0, 4),
+        Err(Error::InvalidConfigurationValue(0))
+    );
+    assert_eq!(
+        tof.calibrate_ambient_lux(100.0, 0),
+        Err(Error::InvalidConfigurationValue(0))
+    );
+}
+
+#[test]
+fn lux_resolution_factor_from_config() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_counts(1000);
+    let mut config = Config::new();
+    config.set_ambient_lux_resolution_factor(1.28).unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    let lux = tof.poll_ambient_lux_single_blocking().unwrap();
+    assert!((lux - 1267.2).abs() < 0.5);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_calibrate_ambient_lux() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_counts(1000);
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        let factor = tof.calibrate_ambient_lux(1267.2, 2).await.unwrap();
+        assert!((factor - 1.28).abs() < 0.001);
+    });
+}
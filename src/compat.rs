@@ -0,0 +1,201 @@
+//! Compatibility wrappers for embedded-hal 0.2 peripherals.
+//!
+//! Enabled with the `embedded-hal-02` feature. Wrap an embedded-hal 0.2 I2C bus, pin
+//! or delay to use it with the driver:
+//!
+//! ```ignore
+//! let i2c = vl6180x::compat::Eh02I2c(i2c);
+//! let mut tof = vl6180x::VL6180X::new(i2c).expect("vl");
+//! ```
+use embedded_hal::{delay::DelayNs, digital, i2c};
+use embedded_hal_02::blocking::delay::DelayUs;
+use embedded_hal_02::blocking::i2c::{Write, WriteRead};
+use embedded_hal_02::digital::v2;
+
+#[cfg(test)]
+mod compat_tests;
+
+/// Error returned by an embedded-hal 0.2 peripheral.
+/// Always reports its kind as `Other`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Eh02Error<E>(pub E);
+
+impl<E: core::fmt::Debug> i2c::Error for Eh02Error<E> {
+    fn kind(&self) -> i2c::ErrorKind {
+        i2c::ErrorKind::Other
+    }
+}
+
+impl<E: core::fmt::Debug> digital::Error for Eh02Error<E> {
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+/// Error returned by [Eh02I2c].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Eh02I2cError<E> {
+    /// Error of the wrapped bus, reports its kind as `Other`
+    Bus(E),
+    /// Consecutive write operations of a transaction don't fit the
+    /// [EH02_WRITE_BUFFER_LEN] byte buffer they are merged in, reports its kind as `Overrun`
+    WriteTooLong,
+}
+
+impl<E: core::fmt::Debug> i2c::Error for Eh02I2cError<E> {
+    fn kind(&self) -> i2c::ErrorKind {
+        match self {
+            Eh02I2cError::Bus(_) => i2c::ErrorKind::Other,
+            Eh02I2cError::WriteTooLong => i2c::ErrorKind::Overrun,
+        }
+    }
+}
+
+/// Size of the buffer [Eh02I2c] merges consecutive write operations in.
+pub const EH02_WRITE_BUFFER_LEN: usize = 16;
+
+/// Wraps an embedded-hal 0.2 `Write + WriteRead` bus so it implements [embedded_hal::i2c::I2c]
+#[derive(Debug, Clone, Copy)]
+pub struct Eh02I2c<I2C>(pub I2C);
+
+impl<I2C, E> i2c::ErrorType for Eh02I2c<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: core::fmt::Debug,
+{
+    type Error = Eh02I2cError<E>;
+}
+
+impl<I2C, E> i2c::I2c for Eh02I2c<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: core::fmt::Debug,
+{
+    /// embedded-hal 0.2 has no transaction support, so the operations are issued in order
+    /// with each run of write operations merged into a single write, which is combined
+    /// with the read that follows it using `write_read`. A read without a preceding
+    /// write is issued with an empty write.
+    ///
+    /// Returns [Eh02I2cError::WriteTooLong] without touching the bus if a run of write
+    /// operations is longer than [EH02_WRITE_BUFFER_LEN] bytes.
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut len = 0;
+        for op in operations.iter() {
+            match op {
+                i2c::Operation::Write(bytes) => len += bytes.len(),
+                i2c::Operation::Read(_) => len = 0,
+            }
+            if len > EH02_WRITE_BUFFER_LEN {
+                return Err(Eh02I2cError::WriteTooLong);
+            }
+        }
+
+        let mut buffer = [0u8; EH02_WRITE_BUFFER_LEN];
+        let mut len = 0;
+        let mut pending_write = false;
+        for op in operations.iter_mut() {
+            match op {
+                i2c::Operation::Write(bytes) => {
+                    buffer[len..len + bytes.len()].copy_from_slice(bytes);
+                    len += bytes.len();
+                    pending_write = true;
+                }
+                i2c::Operation::Read(read) => {
+                    self.0
+                        .write_read(address, &buffer[..len], read)
+                        .map_err(Eh02I2cError::Bus)?;
+                    len = 0;
+                    pending_write = false;
+                }
+            }
+        }
+        if pending_write {
+            self.0
+                .write(address, &buffer[..len])
+                .map_err(Eh02I2cError::Bus)?;
+        }
+        Ok(())
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.0
+            .write_read(address, write, read)
+            .map_err(Eh02I2cError::Bus)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(address, write).map_err(Eh02I2cError::Bus)
+    }
+}
+
+/// Wraps an embedded-hal 0.2 `OutputPin` so it implements [embedded_hal::digital::OutputPin]
+#[derive(Debug, Clone, Copy)]
+pub struct Eh02OutputPin<P>(pub P);
+
+impl<P: v2::OutputPin> digital::ErrorType for Eh02OutputPin<P>
+where
+    P::Error: core::fmt::Debug,
+{
+    type Error = Eh02Error<P::Error>;
+}
+
+impl<P: v2::OutputPin> digital::OutputPin for Eh02OutputPin<P>
+where
+    P::Error: core::fmt::Debug,
+{
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set_low().map_err(Eh02Error)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set_high().map_err(Eh02Error)
+    }
+}
+
+/// Wraps an embedded-hal 0.2 `InputPin` so it implements [embedded_hal::digital::InputPin]
+#[derive(Debug, Clone, Copy)]
+pub struct Eh02InputPin<P>(pub P);
+
+impl<P: v2::InputPin> digital::ErrorType for Eh02InputPin<P>
+where
+    P::Error: core::fmt::Debug,
+{
+    type Error = Eh02Error<P::Error>;
+}
+
+impl<P: v2::InputPin> digital::InputPin for Eh02InputPin<P>
+where
+    P::Error: core::fmt::Debug,
+{
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.0.is_high().map_err(Eh02Error)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.0.is_low().map_err(Eh02Error)
+    }
+}
+
+/// Wraps an embedded-hal 0.2 `DelayUs<u32>` so it implements [embedded_hal::delay::DelayNs]
+#[derive(Debug, Clone, Copy)]
+pub struct Eh02Delay<D>(pub D);
+
+impl<D: DelayUs<u32>> DelayNs for Eh02Delay<D> {
+    /// Rounded up to whole microseconds.
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.delay_us(ns / 1000 + u32::from(ns % 1000 != 0));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.0.delay_us(us);
+    }
+}
@@ -0,0 +1,84 @@
+extern crate std;
+
+use super::*;
+use embedded_hal::i2c::{I2c, Operation};
+use std::{vec, vec::Vec};
+
+#[derive(Debug, PartialEq)]
+enum Transfer {
+    Write(Vec<u8>),
+    WriteRead(Vec<u8>, usize),
+}
+
+/// Records the transfers of an embedded-hal 0.2 bus, reads return 0xAB.
+#[derive(Debug, Default)]
+struct RecordingBus(Vec<Transfer>);
+
+impl Write for RecordingBus {
+    type Error = ();
+
+    fn write(&mut self, _address: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.0.push(Transfer::Write(bytes.to_vec()));
+        Ok(())
+    }
+}
+
+impl WriteRead for RecordingBus {
+    type Error = ();
+
+    fn write_read(&mut self, _address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), ()> {
+        self.0
+            .push(Transfer::WriteRead(bytes.to_vec(), buffer.len()));
+        buffer.fill(0xAB);
+        Ok(())
+    }
+}
+
+#[test]
+fn transaction_issues_every_operation_in_order() {
+    let mut i2c = Eh02I2c(RecordingBus::default());
+    let mut first = [0u8; 1];
+    let mut second = [0u8; 2];
+    i2c.transaction(
+        0x29,
+        &mut [
+            Operation::Write(&[0x00, 0x4D]),
+            Operation::Read(&mut first),
+            Operation::Write(&[0x00, 0x62]),
+            Operation::Read(&mut second),
+            Operation::Write(&[0x00, 0x18]),
+            Operation::Write(&[0x01]),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        i2c.0 .0,
+        vec![
+            Transfer::WriteRead(vec![0x00, 0x4D], 1),
+            Transfer::WriteRead(vec![0x00, 0x62], 2),
+            Transfer::Write(vec![0x00, 0x18, 0x01]),
+        ]
+    );
+    assert_eq!(first, [0xAB]);
+    assert_eq!(second, [0xAB, 0xAB]);
+}
+
+#[test]
+fn transaction_rejects_writes_longer_than_buffer() {
+    let mut i2c = Eh02I2c(RecordingBus::default());
+    let data = [0u8; EH02_WRITE_BUFFER_LEN - 1];
+    assert_eq!(
+        i2c.transaction(
+            0x29,
+            &mut [Operation::Write(&[0x00, 0x40]), Operation::Write(&data)],
+        ),
+        Err(Eh02I2cError::WriteTooLong)
+    );
+    assert_eq!(i2c.0 .0, vec![]);
+
+    // A single write is passed through without the buffer
+    let data = [0u8; 2 * EH02_WRITE_BUFFER_LEN];
+    i2c.write(0x29, &data).unwrap();
+    assert_eq!(i2c.0 .0, vec![Transfer::Write(data.to_vec())]);
+}
@@ -1,8 +1,16 @@
 use super::*;
+use crate::read_measurements::convert_lux_to_raw_ambient;
+use crate::register::{
+    RANGE_CHECK_EARLY_CONVERGENCE_ESTIMATE, RANGE_CHECK_RANGE_IGNORE,
+    RANGE_CHECK_SIGNAL_TO_NOISE_RATIO,
+};
 
 #[cfg(test)]
 mod config_tests;
 
+/// I2C address of the sensor after boot.
+pub(crate) const DEFAULT_ADDRESS: u8 = 0x29;
+
 /// Options for configuring the interrupt trigger condition for ambient measurement.
 #[derive(Debug, Clone, Copy)]
 pub enum AmbientInterruptMode {
@@ -33,15 +41,244 @@ pub enum RangeInterruptMode {
     NewSampleReady = 0b00_000_100,
 }
 
+/// Function of the GPIO0/CE pin, see [Config::set_gpio0_function].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gpio0Function {
+    /// Not used, high impedance
+    Off = 0b0000_0000,
+    /// Interrupt output, asserted as configured by the interrupt modes
+    InterruptOutput = 0b0001_0000,
+    /// Chip enable input, the sensor is held in reset while it is low (Default)
+    XShutdownInput = 0b0100_0000,
+}
+
+/// Function of the GPIO1 pin, see [Config::set_gpio1_function].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gpio1Function {
+    /// Not used, high impedance
+    Off = 0b0000_0000,
+    /// Interrupt output, asserted as configured by the interrupt modes (Default)
+    InterruptOutput = 0b0001_0000,
+}
+
+/// Level of a GPIO interrupt output while the interrupt is asserted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GpioPolarity {
+    /// Low while asserted
+    ActiveLow = 0b0000_0000,
+    /// High while asserted (Default)
+    ActiveHigh = 0b0010_0000,
+}
+
+/// Options for the on-chip history buffer, see [Config::set_history_buffer_mode].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryBufferMode {
+    /// History buffer disabled (Default)
+    Disabled = 0b00,
+    /// The last 16 range measurements are kept
+    Range = 0b01,
+    /// The last 8 ambient light measurements are kept
+    Ambient = 0b11,
+}
+
+/// A range interrupt threshold, either a register value or a distance converted with
+/// the range scaling when it is written to the sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RangeThreshold {
+    /// Register value, in units of the range scaling
+    Code(u8),
+    Mm(u16),
+}
+
+impl RangeThreshold {
+    /// Register value of the threshold, rounded to the nearest `scaling` mm.
+    pub(crate) fn code(self, scaling: u8) -> u8 {
+        match self {
+            RangeThreshold::Code(code) => code,
+            RangeThreshold::Mm(mm) => {
+                let scaling = scaling as u16;
+                ((mm + scaling / 2) / scaling).min(0xFF) as u8
+            }
+        }
+    }
+
+    /// Checks the threshold can be represented with `scaling`, i.e. is at most
+    /// 255 times the scaling.
+    fn check<E>(self, scaling: u8) -> Result<(), Error<E>> {
+        match self {
+            RangeThreshold::Mm(mm) if mm > 0xFF * scaling as u16 => {
+                Err(Error::InvalidConfigurationValue(mm))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An ambient light interrupt threshold, either a register value or an illuminance
+/// converted with the ambient gain, integration period and scaling when it is written to
+/// the sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum AmbientThreshold {
+    /// Register value, in units of the ambient result scaling
+    Code(u16),
+    Lux(f32),
+}
+
+impl AmbientThreshold {
+    /// Register value of the threshold with the ambient settings of `config`.
+    pub(crate) fn code(self, config: &Config) -> u16 {
+        match self {
+            AmbientThreshold::Code(code) => code,
+            AmbientThreshold::Lux(lux) => (Self::scaled_count(config, lux) + 0.5) as u16,
+        }
+    }
+
+    /// Checks the threshold can be represented with the ambient settings of `config`.
+    fn check<E>(self, config: &Config) -> Result<(), Error<E>> {
+        match self {
+            AmbientThreshold::Lux(lux)
+                if !(lux >= 0.0 && Self::scaled_count(config, lux) <= 0xFFFF as f32) =>
+            {
+                Err(Error::InvalidConfigurationValue(lux as u16))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The threshold register compares against the ALS count divided by the scaling.
+    fn scaled_count(config: &Config, lux: f32) -> f32 {
+        convert_lux_to_raw_ambient(config, lux) / config.ambient_scaling as f32
+    }
+}
+
+/// Range ignore threshold and valid height, see [Config::set_range_ignore].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RangeIgnore {
+    /// Return signal rate threshold, in 9.7 fixed point Mcps
+    pub(crate) threshold: u16,
+    pub(crate) valid_height_mm: u16,
+}
+
+impl RangeIgnore {
+    /// Register value of the valid height, rounded to the nearest `scaling` mm.
+    pub(crate) fn valid_height_code(&self, scaling: u8) -> u8 {
+        RangeThreshold::Mm(self.valid_height_mm).code(scaling)
+    }
+
+    fn check<E>(&self, scaling: u8) -> Result<(), Error<E>> {
+        RangeThreshold::Mm(self.valid_height_mm).check(scaling)
+    }
+}
+
+/// Validity checks the sensor applies to each range measurement, see
+/// [Config::set_range_checks].
+///
+/// Measurements failing a check report a [RangeStatusErrorCode] instead of a range.
+/// Relaxing the checks gives more results in bright sunlight or on dark targets,
+/// at the cost of less reliable ones.
+///
+/// ```
+/// # use vl6180x::{Config, RangeChecks};
+/// let mut checks = RangeChecks::new();
+/// checks.set_max_ambient_level_multiplier(4.0).unwrap();
+/// let mut config = Config::new();
+/// config.set_range_checks(checks);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeChecks {
+    pub(super) early_convergence_estimate: bool,
+    pub(super) early_convergence_estimate_threshold: u16,
+    pub(super) signal_to_noise_ratio: bool,
+    pub(super) max_ambient_level_multiplier: u8,
+    pub(super) range_ignore: bool,
+}
+
+impl Default for RangeChecks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RangeChecks {
+    /// Create new range checks with the sensor defaults: early convergence estimate and
+    /// signal to noise ratio checks enabled, range ignore disabled.
+    pub fn new() -> Self {
+        RangeChecks {
+            early_convergence_estimate: true,
+            early_convergence_estimate_threshold: 0,
+            signal_to_noise_ratio: true,
+            max_ambient_level_multiplier: 0xA0,
+            range_ignore: false,
+        }
+    }
+
+    /// Enable or disable the early convergence estimate (ECE) check.
+    ///
+    /// Default = enabled
+    ///
+    /// 0.5 ms into each measurement the sensor estimates whether the return signal
+    /// will converge and aborts the measurement with
+    /// [RangeStatusErrorCode::EarlyConvergenceEstimate] if not, to save power.
+    ///
+    /// Note: The check is only used at 1x
+    /// [range_result_scaler](Config::set_range_result_scaler), it is always disabled
+    /// when upscaling.
+    pub fn set_early_convergence_estimate(&mut self, enabled: bool) {
+        self.early_convergence_estimate = enabled;
+    }
+
+    /// Set the early convergence estimate threshold, the minimum return signal count
+    /// 0.5 ms into the measurement.
+    ///
+    /// Default = 0
+    pub fn set_early_convergence_estimate_threshold(&mut self, threshold: u16) {
+        self.early_convergence_estimate_threshold = threshold;
+    }
+
+    /// Enable or disable the signal to noise ratio (SNR) check.
+    ///
+    /// Default = enabled
+    ///
+    /// Measurements where the ambient light times the
+    /// [max ambient level multiplier](RangeChecks::set_max_ambient_level_multiplier)
+    /// exceeds the return signal fail with [RangeStatusErrorCode::MaxSignalToNoiseRatio].
+    pub fn set_signal_to_noise_ratio(&mut self, enabled: bool) {
+        self.signal_to_noise_ratio = enabled;
+    }
+
+    /// Set the multiplier of the ambient light used by the signal to noise ratio check.
+    ///
+    /// Min = 0; Max = 15.9375; Default = 10
+    ///
+    /// The value is stored in 4.4 fixed point, so is rounded to a multiple of 1/16.
+    /// A lower multiplier accepts measurements in brighter ambient light.
+    pub fn set_max_ambient_level_multiplier(&mut self, multiplier: f32) -> Result<(), Error<()>> {
+        if !(0.0..16.0).contains(&multiplier) {
+            return Err(Error::InvalidConfigurationValue(multiplier as u16));
+        }
+        self.max_ambient_level_multiplier = (multiplier * 16.0 + 0.5).min(0xFF as f32) as u8;
+        Ok(())
+    }
+
+    /// Enable or disable the range ignore check, with the threshold and valid height set
+    /// with [Config::set_range_ignore].
+    ///
+    /// Default = disabled
+    pub fn set_range_ignore(&mut self, enabled: bool) {
+        self.range_ignore = enabled;
+    }
+}
+
 /// Config information for the driver.
 #[derive(Debug, Clone, Copy)]
 pub struct Config {
-    pub(super) ptp_offset: u8,
+    pub(super) ptp_offset: i8,
+    pub(super) range_offset_mm: Option<i8>,
 
     pub(super) address: u8,
     pub(super) range_scaling: u8,
     pub(super) ambient_scaling: u8,
-    pub(super) poll_max_loop: u16,
+    pub(super) boot_timeout_us: u32,
 
     // Performance tuning
     pub(super) readout_averaging_period_multiplier: u8,
@@ -49,18 +286,36 @@ pub struct Config {
     pub(super) range_max_convergence_time: u8,
     pub(super) range_inter_measurement_period: u16,
     pub(super) range_vhv_recalibration_rate: u8,
+    pub(super) range_crosstalk_compensation_rate: u16,
+    pub(super) range_ignore: RangeIgnore,
+    pub(super) range_checks: RangeChecks,
 
     pub(super) ambient_analogue_gain_level: u8,
     pub(super) ambient_integration_period: u16,
+    pub(super) ambient_lux_resolution_factor: f32,
     pub(super) ambient_inter_measurement_period: u16,
 
     // Interrupt modes
     pub(super) range_interrupt_mode: RangeInterruptMode,
     pub(super) ambient_interrupt_mode: AmbientInterruptMode,
-    pub(super) range_low_interrupt_threshold: u8,
-    pub(super) range_high_interrupt_threshold: u8,
-    pub(super) ambient_low_interrupt_threshold: u16,
-    pub(super) ambient_high_interrupt_threshold: u16,
+    pub(super) range_low_interrupt_threshold: RangeThreshold,
+    pub(super) range_high_interrupt_threshold: RangeThreshold,
+    pub(super) ambient_low_interrupt_threshold: AmbientThreshold,
+    pub(super) ambient_high_interrupt_threshold: AmbientThreshold,
+
+    pub(super) history_buffer_mode: HistoryBufferMode,
+
+    // GPIO pins
+    pub(super) gpio0_function: Gpio0Function,
+    pub(super) gpio0_polarity: GpioPolarity,
+    pub(super) gpio1_function: Gpio1Function,
+    pub(super) gpio1_polarity: GpioPolarity,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Config {
@@ -69,9 +324,10 @@ impl Config {
     /// Defaults are based on values from [ST application note AN4545](https://www.st.com/resource/en/application_note/an4545-vl6180x-basic-ranging-application-note-stmicroelectronics.pdf)
     pub fn new() -> Self {
         Config {
-            address: 0x29,
+            address: DEFAULT_ADDRESS,
             ptp_offset: 0,
-            poll_max_loop: 500,
+            range_offset_mm: None,
+            boot_timeout_us: 10_000,
 
             range_scaling: 1,
             ambient_scaling: 1,
@@ -82,29 +338,51 @@ impl Config {
             range_max_convergence_time: 49,
             range_inter_measurement_period: 100,
             range_vhv_recalibration_rate: 255,
+            range_crosstalk_compensation_rate: 0,
+            range_ignore: RangeIgnore {
+                threshold: 0,
+                valid_height_mm: 0,
+            },
+            range_checks: RangeChecks::new(),
 
             ambient_analogue_gain_level: 0,
             ambient_integration_period: 100,
+            ambient_lux_resolution_factor: 0.32,
             ambient_inter_measurement_period: 500,
 
             // Interrupt modes
             range_interrupt_mode: RangeInterruptMode::NewSampleReady,
             ambient_interrupt_mode: AmbientInterruptMode::NewSampleReady,
-            range_low_interrupt_threshold: 0,
-            range_high_interrupt_threshold: 0xFF,
-            ambient_low_interrupt_threshold: 0,
-            ambient_high_interrupt_threshold: 0xFFFF,
-            // Implement in the future
-            // TODO: range_ignore
-            // TODO: ambient_lux_resolution_factor
+            range_low_interrupt_threshold: RangeThreshold::Code(0),
+            range_high_interrupt_threshold: RangeThreshold::Code(0xFF),
+            ambient_low_interrupt_threshold: AmbientThreshold::Code(0),
+            ambient_high_interrupt_threshold: AmbientThreshold::Code(0xFFFF),
+
+            history_buffer_mode: HistoryBufferMode::Disabled,
+
+            // GPIO pins
+            gpio0_function: Gpio0Function::XShutdownInput,
+            gpio0_polarity: GpioPolarity::ActiveHigh,
+            gpio1_function: Gpio1Function::InterruptOutput,
+            gpio1_polarity: GpioPolarity::ActiveHigh,
         }
     }
 
     /// Set the max number of loops during polling measurement.
     ///
-    /// Default = 500;
-    pub fn set_poll_max_loop(&mut self, max_loop: u16) {
-        self.poll_max_loop = max_loop;
+    /// Has no effect, the measurement timeouts are derived from the
+    /// [max convergence time](Config::set_range_max_convergence_time), the
+    /// [integration period](Config::set_ambient_integration_period) and the inter-measurement
+    /// periods, and are measured with the [WaitStrategy](crate::WaitStrategy).
+    #[deprecated(note = "measurement timeouts are derived from the measurement timing")]
+    pub fn set_poll_max_loop(&mut self, _max_loop: u16) {}
+
+    /// Set the time (us) to wait for the device to boot after it has been powered on,
+    /// in addition to the boot time from the datasheet.
+    ///
+    /// Default = 10000us;
+    pub fn set_boot_timeout_us(&mut self, timeout_us: u32) {
+        self.boot_timeout_us = timeout_us;
     }
     /// The range max convergence time (ms) is made up of the convergence time and sampling period.
     ///
@@ -114,7 +392,7 @@ impl Config {
     /// allowed to complete and can reduce the power consumption when no target is present. We
     /// recommend a value of 30ms for the max convergence time as a suitable starting point.
     pub fn set_range_max_convergence_time(&mut self, time_ms: u8) -> Result<(), Error<()>> {
-        if time_ms < 2 || time_ms > 63 {
+        if !(2..=63).contains(&time_ms) {
             return Err(Error::InvalidConfigurationValue(time_ms as u16));
         }
         self.range_max_convergence_time = time_ms;
@@ -162,6 +440,67 @@ impl Config {
         self.range_vhv_recalibration_rate = rate_vhv;
     }
 
+    /// Set the part-to-part range offset in mm, replacing the factory calibrated value.
+    ///
+    /// Default = factory calibrated value read from the sensor on initialization
+    ///
+    /// The offset is added to every range measurement and is scaled with the
+    /// [range_result_scaler](Config::set_range_result_scaler). It is usually obtained once with
+    /// [calibrate_range_offset](crate::VL6180X::calibrate_range_offset), stored, and then
+    /// applied here on later boots.
+    pub fn set_range_offset_mm(&mut self, offset_mm: i8) {
+        self.range_offset_mm = Some(offset_mm);
+    }
+
+    /// Set the range crosstalk compensation rate, in 9.7 fixed point Mcps.
+    ///
+    /// Default = 0 (no compensation)
+    ///
+    /// A cover window in front of the sensor reflects part of the emitted light back,
+    /// which makes range measurements read short. The value is specific to each
+    /// installation and is usually obtained once with
+    /// [calibrate_crosstalk](crate::VL6180X::calibrate_crosstalk), stored, and then applied
+    /// here on later boots.
+    pub fn set_range_crosstalk_compensation_rate(&mut self, rate: u16) {
+        self.range_crosstalk_compensation_rate = rate;
+    }
+
+    /// Set the range ignore threshold and valid height, to reject reflections from a
+    /// cover window when there is no target in front of the sensor.
+    ///
+    /// Default = threshold 0 Mcps; valid height 0 mm
+    ///
+    /// When the check is enabled with [RangeChecks::set_range_ignore], measurements
+    /// closer than `valid_height_mm` with a return signal rate below `threshold_mcps`
+    /// fail with [RangeStatusErrorCode::RangeIgnore]. The threshold
+    /// must be in 0 - 511.99 Mcps. The valid height is scaled with the
+    /// [range_result_scaler](Config::set_range_result_scaler) and must be representable
+    /// with the scaling set, i.e. 0 - 255 mm at 1x, 0 - 510 mm at 2x and 0 - 765 mm at 3x.
+    /// Otherwise [Error::InvalidConfigurationValue] is returned.
+    pub fn set_range_ignore(
+        &mut self,
+        threshold_mcps: f32,
+        valid_height_mm: u16,
+    ) -> Result<(), Error<()>> {
+        if !(0.0..512.0).contains(&threshold_mcps) {
+            return Err(Error::InvalidConfigurationValue(threshold_mcps as u16));
+        }
+        let range_ignore = RangeIgnore {
+            threshold: (threshold_mcps * 128.0 + 0.5).min(0xFFFF as f32) as u16,
+            valid_height_mm,
+        };
+        range_ignore.check(self.range_scaling)?;
+        self.range_ignore = range_ignore;
+        Ok(())
+    }
+
+    /// Set the validity checks applied to each range measurement, see [RangeChecks].
+    ///
+    /// Default = [RangeChecks::new]
+    pub fn set_range_checks(&mut self, checks: RangeChecks) {
+        self.range_checks = checks;
+    }
+
     /// Set ambient result scaler
     /// Min = 1x; Max = 15x; Default = 1x
     ///
@@ -169,11 +508,17 @@ impl Config {
     ///
     /// In addition to analogue gain, the VL6180X has a scaler that multiplies the ALS count prior to the result being read.
     /// This value, in addition to the analogue gain is useful in very low light conditions to increase the dynamic range.
+    ///
+    /// Ambient interrupt thresholds set in lux must be representable with the new
+    /// scaling, see [Config::set_ambient_high_interrupt_threshold_lux].
     pub fn set_ambient_result_scaler(&mut self, scaler: u8) -> Result<(), Error<()>> {
-        if scaler < 1 || scaler > 15 {
+        if !(1..=15).contains(&scaler) {
             return Err(Error::InvalidConfigurationValue(scaler as u16));
         }
-        self.ambient_scaling = scaler;
+        let mut config = *self;
+        config.ambient_scaling = scaler;
+        config.check_ambient_thresholds()?;
+        *self = config;
         Ok(())
     }
 
@@ -186,10 +531,19 @@ impl Config {
     /// raw values in units of 2 mm or 3 mm instead. In other words, a bigger scaling
     /// factor increases the sensor's potential maximum range but reduces its
     /// resolution.
+    ///
+    /// Range interrupt thresholds set in mm and the [range ignore](Config::set_range_ignore)
+    /// valid height must be representable with the new scaling, see
+    /// [Config::set_range_high_interrupt_threshold_mm].
     pub fn set_range_result_scaler(&mut self, scaler: u8) -> Result<(), Error<()>> {
-        if scaler < 1 || scaler > 3 {
+        if !(1..=3).contains(&scaler) {
             return Err(Error::InvalidConfigurationValue(scaler as u16));
         }
+        self.range_low_interrupt_threshold.check(scaler)?;
+        self.range_high_interrupt_threshold.check(scaler)?;
+        if self.range_checks.range_ignore {
+            self.range_ignore.check(scaler)?;
+        }
         self.range_scaling = scaler;
         Ok(())
     }
@@ -213,11 +567,17 @@ impl Config {
     /// 6: ALS Gain = 20
     ///
     /// 7: ALS Gain = 40
+    ///
+    /// Ambient interrupt thresholds set in lux must be representable with the new
+    /// gain, see [Config::set_ambient_high_interrupt_threshold_lux].
     pub fn set_ambient_analogue_gain_level(&mut self, level: u8) -> Result<(), Error<()>> {
         if level > 7 {
             return Err(Error::InvalidConfigurationValue(level as u16));
         }
-        self.ambient_analogue_gain_level = level;
+        let mut config = *self;
+        config.ambient_analogue_gain_level = level;
+        config.check_ambient_thresholds()?;
+        *self = config;
         Ok(())
     }
 
@@ -228,11 +588,41 @@ impl Config {
     /// The integration period is the time over which a single ambient light
     /// measurement is made. Integration times in the range 50-100ms are
     /// recommended to reduce impact of light flicker from artificial lighting
+    ///
+    /// Ambient interrupt thresholds set in lux must be representable with the new
+    /// period, see [Config::set_ambient_high_interrupt_threshold_lux].
     pub fn set_ambient_integration_period(&mut self, time_ms: u16) -> Result<(), Error<()>> {
-        if time_ms < 1 || time_ms > 256 {
-            return Err(Error::InvalidConfigurationValue(time_ms as u16));
+        if !(1..=256).contains(&time_ms) {
+            return Err(Error::InvalidConfigurationValue(time_ms));
         }
-        self.ambient_integration_period = time_ms;
+        let mut config = *self;
+        config.ambient_integration_period = time_ms;
+        config.check_ambient_thresholds()?;
+        *self = config;
+        Ok(())
+    }
+
+    /// Set the lux resolution factor, the lux per ALS count at 1x gain and
+    /// 100ms integration period, used to convert ambient light measurements to lux.
+    ///
+    /// Default = 0.32 (sensor without cover window)
+    ///
+    /// A tinted cover window in front of the sensor attenuates the light, so the factor
+    /// is specific to each installation. It is usually obtained once with
+    /// [calibrate_ambient_lux](crate::VL6180X::calibrate_ambient_lux), stored, and then
+    /// applied here on later boots. The factor must be positive and finite, otherwise
+    /// [Error::InvalidConfigurationValue] is returned.
+    ///
+    /// Ambient interrupt thresholds set in lux must be representable with the new
+    /// factor, see [Config::set_ambient_high_interrupt_threshold_lux].
+    pub fn set_ambient_lux_resolution_factor(&mut self, factor: f32) -> Result<(), Error<()>> {
+        if !(factor > 0.0 && factor.is_finite()) {
+            return Err(Error::InvalidConfigurationValue(factor as u16));
+        }
+        let mut config = *self;
+        config.ambient_lux_resolution_factor = factor;
+        config.check_ambient_thresholds()?;
+        *self = config;
         Ok(())
     }
 
@@ -276,22 +666,58 @@ impl Config {
         self.range_interrupt_mode = interrupt_mode;
     }
 
-    /// Set the low threshold for range interrupt.
+    /// Set the low threshold for range interrupt, as a register value.
     ///
     /// Default = 0;
     ///
-    /// Note: This value will be multiplied by the [range_result_scaler](Config::set_range_result_scaler) used
+    /// Note: This value will be multiplied by the [range_result_scaler](Config::set_range_result_scaler) used,
+    /// use [Config::set_range_low_interrupt_threshold_mm] to set it in mm.
     pub fn set_range_low_interrupt_threshold(&mut self, threshold: u8) {
-        self.range_low_interrupt_threshold = threshold;
+        self.range_low_interrupt_threshold = RangeThreshold::Code(threshold);
     }
 
-    /// Set the high threshold for range interrupt.
+    /// Set the high threshold for range interrupt, as a register value.
     ///
     /// Default = 255;
     ///
-    /// Note: This value will be multiplied by the [range_result_scaler](Config::set_range_result_scaler) used
+    /// Note: This value will be multiplied by the [range_result_scaler](Config::set_range_result_scaler) used,
+    /// use [Config::set_range_high_interrupt_threshold_mm] to set it in mm.
     pub fn set_range_high_interrupt_threshold(&mut self, threshold: u8) {
+        self.range_high_interrupt_threshold = RangeThreshold::Code(threshold);
+    }
+
+    /// Set the low threshold for range interrupt in mm.
+    ///
+    /// Default = 0;
+    ///
+    /// See [Config::set_range_high_interrupt_threshold_mm].
+    pub fn set_range_low_interrupt_threshold_mm(
+        &mut self,
+        threshold_mm: u16,
+    ) -> Result<(), Error<()>> {
+        let threshold = RangeThreshold::Mm(threshold_mm);
+        threshold.check(self.range_scaling)?;
+        self.range_low_interrupt_threshold = threshold;
+        Ok(())
+    }
+
+    /// Set the high threshold for range interrupt in mm.
+    ///
+    /// Default = 255 times the [range_result_scaler](Config::set_range_result_scaler);
+    ///
+    /// The threshold is converted with the range scaling when it is written to the sensor,
+    /// rounded to the nearest multiple of the scaling. It must be representable with the
+    /// scaling set, i.e. 0 - 255 mm at 1x, 0 - 510 mm at 2x and 0 - 765 mm at 3x, otherwise
+    /// [Error::InvalidConfigurationValue] is returned. Set the scaling first, lowering it
+    /// afterwards fails if the threshold would no longer be representable.
+    pub fn set_range_high_interrupt_threshold_mm(
+        &mut self,
+        threshold_mm: u16,
+    ) -> Result<(), Error<()>> {
+        let threshold = RangeThreshold::Mm(threshold_mm);
+        threshold.check(self.range_scaling)?;
         self.range_high_interrupt_threshold = threshold;
+        Ok(())
     }
 
     /// Set the ambient light sensor interrupt mode. Possible values:
@@ -313,20 +739,259 @@ impl Config {
     ///
     /// Default = 0x0;
     ///
-    /// Note: Threshold is in raw device value not lux.
+    /// Note: Threshold is in raw device value not lux, see
+    /// [Config::set_ambient_low_interrupt_threshold_lux].
     /// This value will be multiplied by the [ambient_result_scaler](Config::set_ambient_result_scaler) used
     pub fn set_ambient_low_interrupt_threshold(&mut self, threshold: u16) {
-        self.ambient_low_interrupt_threshold = threshold;
+        self.ambient_low_interrupt_threshold = AmbientThreshold::Code(threshold);
     }
 
     /// Set the high threshold for ambient interrupt.
     ///
     /// Default = 0xFFFF;
     ///
-    /// Note: Threshold is in raw device value not lux.
+    /// Note: Threshold is in raw device value not lux, see
+    /// [Config::set_ambient_high_interrupt_threshold_lux].
     /// This value will be multiplied by the [ambient_result_scaler](Config::set_ambient_result_scaler) used
     pub fn set_ambient_high_interrupt_threshold(&mut self, threshold: u16) {
+        self.ambient_high_interrupt_threshold = AmbientThreshold::Code(threshold);
+    }
+
+    /// Set the low threshold for ambient interrupt in lux.
+    ///
+    /// Default = 0;
+    ///
+    /// See [Config::set_ambient_high_interrupt_threshold_lux].
+    pub fn set_ambient_low_interrupt_threshold_lux(&mut self, lux: f32) -> Result<(), Error<()>> {
+        let threshold = AmbientThreshold::Lux(lux);
+        threshold.check(self)?;
+        self.ambient_low_interrupt_threshold = threshold;
+        Ok(())
+    }
+
+    /// Set the high threshold for ambient interrupt in lux.
+    ///
+    /// Default = highest value the ambient settings can represent;
+    ///
+    /// The threshold is converted to a raw value with the
+    /// [analogue gain](Config::set_ambient_analogue_gain_level),
+    /// [integration period](Config::set_ambient_integration_period) and
+    /// [result scaler](Config::set_ambient_result_scaler) when it is written to the sensor,
+    /// the inverse of the conversion of [read_ambient_lux](crate::VL6180X::read_ambient_lux).
+    /// Returns [Error::InvalidConfigurationValue] if the threshold is negative or
+    /// above what the current settings can represent, e.g. about 20760 lux at the default
+    /// gain, period and scaling. Set those first, changing them afterwards fails if
+    /// the threshold would no longer be representable.
+    pub fn set_ambient_high_interrupt_threshold_lux(&mut self, lux: f32) -> Result<(), Error<()>> {
+        let threshold = AmbientThreshold::Lux(lux);
+        threshold.check(self)?;
         self.ambient_high_interrupt_threshold = threshold;
+        Ok(())
+    }
+
+    /// Set the history buffer mode. Possible values:
+    ///
+    /// Disabled (this is the default)
+    ///
+    /// Range (the last 16 range measurements are kept)
+    ///
+    /// Ambient (the last 8 ambient light measurements are kept)
+    ///
+    /// VL6180X datasheet: SYSTEM__HISTORY_CTRL and RESULT__HISTORY_BUFFER_x registers
+    ///
+    /// The sensor keeps the most recent measurements of the selected type so a host
+    /// in continuous mode can read them all at once, see
+    /// [read_range_history_mm](crate::VL6180X::read_range_history_mm) and
+    /// [read_ambient_history](crate::VL6180X::read_ambient_history).
+    pub fn set_history_buffer_mode(&mut self, mode: HistoryBufferMode) {
+        self.history_buffer_mode = mode;
+    }
+
+    /// Set the function of the GPIO0/CE pin. Possible values:
+    ///
+    /// Off
+    ///
+    /// Interrupt Output
+    ///
+    /// XShutdown Input (this is the default)
+    ///
+    /// VL6180X datasheet: SYSTEM__MODE_GPIO0 register
+    ///
+    /// Note: GPIO0 is the only shutdown input of the sensor. Once it is configured as something
+    /// else, the sensor can only be reset by cycling its power, so the x shutdown pin methods such
+    /// as [power_off](crate::VL6180X::power_off) must not be used, and the host pin connected to
+    /// GPIO0 must be an input. An interrupt output is only enabled while an
+    /// interrupt mode is enabled.
+    pub fn set_gpio0_function(&mut self, function: Gpio0Function) {
+        self.gpio0_function = function;
+    }
+
+    /// Set the polarity of GPIO0 when it is an interrupt output.
+    ///
+    /// Default = active high;
+    pub fn set_gpio0_polarity(&mut self, polarity: GpioPolarity) {
+        self.gpio0_polarity = polarity;
+    }
+
+    /// Set the function of the GPIO1 pin. Possible values:
+    ///
+    /// Off
+    ///
+    /// Interrupt Output (this is the default)
+    ///
+    /// VL6180X datasheet: SYSTEM__MODE_GPIO1 register
+    ///
+    /// Note: an interrupt output is only enabled while an interrupt mode is enabled.
+    pub fn set_gpio1_function(&mut self, function: Gpio1Function) {
+        self.gpio1_function = function;
+    }
+
+    /// Set the polarity of GPIO1 when it is an interrupt output.
+    ///
+    /// Default = active high;
+    pub fn set_gpio1_polarity(&mut self, polarity: GpioPolarity) {
+        self.gpio1_polarity = polarity;
+    }
+
+    /// Checks the values that depend on each other, which the setters only check
+    /// against the values set before them.
+    pub(crate) fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !(0x08..=0x77).contains(&self.address) {
+            return Err(Error::InvalidAddress(self.address));
+        }
+        if !(1..=3).contains(&self.range_scaling) {
+            return Err(Error::InvalidConfigurationValue(self.range_scaling as u16));
+        }
+        if self.ambient_analogue_gain_level > 7 {
+            return Err(Error::InvalidConfigurationValue(
+                self.ambient_analogue_gain_level as u16,
+            ));
+        }
+        if self.range_checks.range_ignore {
+            self.range_ignore.check(self.range_scaling)?;
+        }
+        let min_eq_val = ((self.range_max_convergence_time + 5) as f32 / 0.9) as u16;
+        if self.range_inter_measurement_period < min_eq_val {
+            return Err(Error::InvalidConfigurationValue(
+                self.range_inter_measurement_period,
+            ));
+        }
+        let min_eq_val = ((self.ambient_integration_period as f32 * 1.1) / 0.9) as u16;
+        if self.ambient_inter_measurement_period < min_eq_val {
+            return Err(Error::InvalidConfigurationValue(
+                self.ambient_inter_measurement_period,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Copy the interrupt thresholds of `other`.
+    pub(crate) fn copy_interrupt_thresholds(&mut self, other: &Config) {
+        self.range_low_interrupt_threshold = other.range_low_interrupt_threshold;
+        self.range_high_interrupt_threshold = other.range_high_interrupt_threshold;
+        self.ambient_low_interrupt_threshold = other.ambient_low_interrupt_threshold;
+        self.ambient_high_interrupt_threshold = other.ambient_high_interrupt_threshold;
+    }
+
+    /// Copy of the config with the range thresholds set to `low_mm` and `high_mm`.
+    pub(crate) fn with_range_thresholds_mm<E>(
+        &self,
+        low_mm: u16,
+        high_mm: u16,
+    ) -> Result<Config, Error<E>> {
+        let low = RangeThreshold::Mm(low_mm);
+        let high = RangeThreshold::Mm(high_mm);
+        low.check(self.range_scaling)?;
+        high.check(self.range_scaling)?;
+        if low_mm > high_mm {
+            return Err(Error::InvalidConfigurationValue(low_mm));
+        }
+        let mut config = *self;
+        config.range_low_interrupt_threshold = low;
+        config.range_high_interrupt_threshold = high;
+        Ok(config)
+    }
+
+    /// Checks the ambient thresholds set in lux can be represented.
+    fn check_ambient_thresholds<E>(&self) -> Result<(), Error<E>> {
+        self.ambient_low_interrupt_threshold.check(self)?;
+        self.ambient_high_interrupt_threshold.check(self)
+    }
+
+    /// Copy of the config with the ambient thresholds set to `low` and `high`.
+    pub(crate) fn with_ambient_thresholds<E>(
+        &self,
+        low: u16,
+        high: u16,
+    ) -> Result<Config, Error<E>> {
+        if low > high {
+            return Err(Error::InvalidConfigurationValue(low));
+        }
+        let mut config = *self;
+        config.ambient_low_interrupt_threshold = AmbientThreshold::Code(low);
+        config.ambient_high_interrupt_threshold = AmbientThreshold::Code(high);
+        Ok(config)
+    }
+
+    /// Value of SYSTEM__MODE_GPIO0.
+    pub(crate) fn gpio0_mode(&self) -> u8 {
+        match self.gpio0_function {
+            Gpio0Function::InterruptOutput if self.interrupts_enabled() => {
+                Gpio0Function::InterruptOutput as u8 | self.gpio0_polarity as u8
+            }
+            // An inverted shutdown input would hold the sensor in reset
+            Gpio0Function::XShutdownInput => {
+                Gpio0Function::XShutdownInput as u8 | GpioPolarity::ActiveHigh as u8
+            }
+            _ => Gpio0Function::Off as u8,
+        }
+    }
+
+    /// Value of SYSTEM__MODE_GPIO1.
+    pub(crate) fn gpio1_mode(&self) -> u8 {
+        let function = match self.gpio1_function {
+            Gpio1Function::InterruptOutput if !self.interrupts_enabled() => Gpio1Function::Off,
+            function => function,
+        };
+        function as u8 | self.gpio1_polarity as u8
+    }
+
+    /// Polarity of the interrupt output, GPIO1 if it is an interrupt output, otherwise GPIO0.
+    /// `None` if neither pin is an interrupt output.
+    pub(crate) fn interrupt_polarity(&self) -> Option<GpioPolarity> {
+        if self.gpio1_function == Gpio1Function::InterruptOutput {
+            Some(self.gpio1_polarity)
+        } else if self.gpio0_function == Gpio0Function::InterruptOutput {
+            Some(self.gpio0_polarity)
+        } else {
+            None
+        }
+    }
+
+    /// Value of SYSTEM__INTERRUPT_CONFIG_GPIO.
+    pub(crate) fn interrupt_config(&self) -> u8 {
+        self.range_interrupt_mode as u8 | self.ambient_interrupt_mode as u8
+    }
+
+    /// Value of SYSRANGE__RANGE_CHECK_ENABLES.
+    pub(crate) fn range_check_enables(&self) -> u8 {
+        let checks = &self.range_checks;
+        let mut enables = 0;
+        // ECE is only used at 1x scaling
+        if checks.early_convergence_estimate && self.range_scaling == 1 {
+            enables |= RANGE_CHECK_EARLY_CONVERGENCE_ESTIMATE;
+        }
+        if checks.range_ignore {
+            enables |= RANGE_CHECK_RANGE_IGNORE;
+        }
+        if checks.signal_to_noise_ratio {
+            enables |= RANGE_CHECK_SIGNAL_TO_NOISE_RATIO;
+        }
+        enables
+    }
+
+    fn interrupts_enabled(&self) -> bool {
+        self.interrupt_config() != 0
     }
 
     /// Set the i2c address for the initial connection
@@ -30,3 +30,218 @@ fn set_range_max_convergence_time_value_valid() {
     assert_eq!(config.set_range_max_convergence_time(20), Ok(()))
 }
 
+#[test]
+fn validate_range_inter_measurement_period() {
+    let mut config = Config::new();
+    config.set_range_max_convergence_time(10).unwrap();
+    config.set_range_inter_measurement_period(20).unwrap();
+    assert_eq!(config.validate::<()>(), Ok(()));
+
+    // 49ms + 5ms readout no longer fits in 20ms * 0.9
+    config.set_range_max_convergence_time(49).unwrap();
+    assert_eq!(
+        config.validate::<()>(),
+        Err(Error::InvalidConfigurationValue(20))
+    );
+}
+
+#[test]
+fn validate_gain_level_and_scaling() {
+    let mut config = Config::new();
+    config.ambient_analogue_gain_level = 8;
+    assert_eq!(
+        config.validate::<()>(),
+        Err(Error::InvalidConfigurationValue(8))
+    );
+
+    let mut config = Config::new();
+    config.range_scaling = 0;
+    assert_eq!(
+        config.validate::<()>(),
+        Err(Error::InvalidConfigurationValue(0))
+    );
+    config.range_scaling = 4;
+    assert_eq!(
+        config.validate::<()>(),
+        Err(Error::InvalidConfigurationValue(4))
+    );
+}
+
+#[test]
+fn gpio_modes() {
+    let mut config = Config::new();
+    assert_eq!(config.gpio0_mode(), 0x60);
+    assert_eq!(config.gpio1_mode(), 0x30);
+    assert_eq!(config.interrupt_polarity(), Some(GpioPolarity::ActiveHigh));
+
+    // The polarity only applies to the interrupt output
+    config.set_gpio0_polarity(GpioPolarity::ActiveLow);
+    assert_eq!(config.gpio0_mode(), 0x60);
+
+    config.set_gpio0_function(Gpio0Function::InterruptOutput);
+    config.set_gpio1_function(Gpio1Function::Off);
+    assert_eq!(config.gpio0_mode(), 0x10);
+    assert_eq!(config.gpio1_mode(), 0x20);
+    assert_eq!(config.interrupt_polarity(), Some(GpioPolarity::ActiveLow));
+
+    config.set_range_interrupt_mode(RangeInterruptMode::Disabled);
+    config.set_ambient_interrupt_mode(AmbientInterruptMode::Disabled);
+    assert_eq!(config.gpio0_mode(), 0x00);
+}
+
+#[test]
+fn range_thresholds_mm_representable_with_scaling() {
+    let mut config = Config::new();
+    assert_eq!(
+        config.set_range_high_interrupt_threshold_mm(256),
+        Err(Error::InvalidConfigurationValue(256))
+    );
+    config.set_range_result_scaler(3).unwrap();
+    assert_eq!(config.set_range_high_interrupt_threshold_mm(765), Ok(()));
+    assert_eq!(
+        config.set_range_low_interrupt_threshold_mm(766),
+        Err(Error::InvalidConfigurationValue(766))
+    );
+    assert_eq!(
+        config.set_range_result_scaler(2),
+        Err(Error::InvalidConfigurationValue(765))
+    );
+    assert_eq!(config.range_high_interrupt_threshold.code(3), 255);
+}
+
+#[test]
+fn range_threshold_code_rounds_to_scaling() {
+    assert_eq!(RangeThreshold::Mm(100).code(1), 100);
+    assert_eq!(RangeThreshold::Mm(100).code(3), 33);
+    assert_eq!(RangeThreshold::Mm(101).code(3), 34);
+    assert_eq!(RangeThreshold::Code(100).code(3), 100);
+}
+
+#[test]
+fn ambient_threshold_lux_inverts_lux_conversion() {
+    let mut config = Config::new();
+    config.set_ambient_analogue_gain_level(3).unwrap();
+    config
+        .set_ambient_high_interrupt_threshold_lux(50.0)
+        .unwrap();
+    let code = config.ambient_high_interrupt_threshold.code(&config);
+    let lux = read_measurements::convert_raw_ambient_to_lux(&config, code);
+    assert!((lux - 50.0).abs() < 0.1);
+
+    config.set_ambient_result_scaler(2).unwrap();
+    assert_eq!(
+        config.ambient_high_interrupt_threshold.code(&config),
+        code / 2
+    );
+}
+
+#[test]
+fn ambient_threshold_lux_representable_with_settings() {
+    let mut config = Config::new();
+    assert_eq!(
+        config.set_ambient_low_interrupt_threshold_lux(-1.0),
+        Err(Error::InvalidConfigurationValue(0))
+    );
+    assert_eq!(
+        config.set_ambient_high_interrupt_threshold_lux(21_000.0),
+        Err(Error::InvalidConfigurationValue(21_000))
+    );
+    config
+        .set_ambient_high_interrupt_threshold_lux(20_000.0)
+        .unwrap();
+    assert_eq!(
+        config.set_ambient_analogue_gain_level(1),
+        Err(Error::InvalidConfigurationValue(20_000))
+    );
+    assert_eq!(config.ambient_analogue_gain_level, 0);
+    assert_eq!(config.set_ambient_result_scaler(2), Ok(()));
+    assert_eq!(config.set_ambient_analogue_gain_level(1), Ok(()));
+}
+
+#[test]
+fn range_ignore_validation() {
+    let mut config = Config::new();
+    assert_eq!(
+        config.set_range_ignore(512.0, 20),
+        Err(Error::InvalidConfigurationValue(512))
+    );
+    assert_eq!(
+        config.set_range_ignore(1.0, 256),
+        Err(Error::InvalidConfigurationValue(256))
+    );
+    config.set_range_result_scaler(3).unwrap();
+    config.set_range_ignore(1.5, 300).unwrap();
+    let mut checks = RangeChecks::new();
+    checks.set_range_ignore(true);
+    config.set_range_checks(checks);
+    let range_ignore = config.range_ignore;
+    assert_eq!(range_ignore.threshold, 192);
+    assert_eq!(range_ignore.valid_height_code(3), 100);
+    assert_eq!(
+        config.set_range_result_scaler(1),
+        Err(Error::InvalidConfigurationValue(300))
+    );
+    // Also checked when the scaling is changed without the setter
+    config.range_scaling = 1;
+    assert_eq!(
+        config.validate::<()>(),
+        Err(Error::InvalidConfigurationValue(300))
+    );
+    // Unrelated setters are not affected
+    assert_eq!(config.set_range_inter_measurement_period(200), Ok(()));
+    config.set_range_checks(RangeChecks::new());
+    assert_eq!(config.validate::<()>(), Ok(()));
+    config.range_scaling = 3;
+    assert_eq!(config.set_range_result_scaler(1), Ok(()));
+}
+
+#[test]
+fn range_check_enables() {
+    let mut config = Config::new();
+    assert_eq!(config.range_check_enables(), 0x11);
+    config.set_range_result_scaler(2).unwrap();
+    assert_eq!(config.range_check_enables(), 0x10);
+
+    // The threshold alone does not enable range ignore
+    config.set_range_ignore(1.0, 20).unwrap();
+    assert_eq!(config.range_check_enables(), 0x10);
+    let mut checks = RangeChecks::new();
+    checks.set_signal_to_noise_ratio(false);
+    checks.set_range_ignore(true);
+    config.set_range_checks(checks);
+    assert_eq!(config.range_check_enables(), 0x02);
+    config.set_range_result_scaler(1).unwrap();
+    assert_eq!(config.range_check_enables(), 0x03);
+    checks.set_range_ignore(false);
+    config.set_range_checks(checks);
+    assert_eq!(config.range_check_enables(), 0x01);
+}
+
+#[test]
+fn max_ambient_level_multiplier() {
+    let mut checks = RangeChecks::new();
+    assert_eq!(checks.max_ambient_level_multiplier, 0xA0);
+    assert_eq!(checks.set_max_ambient_level_multiplier(4.5), Ok(()));
+    assert_eq!(checks.max_ambient_level_multiplier, 0x48);
+    assert_eq!(
+        checks.set_max_ambient_level_multiplier(16.0),
+        Err(Error::InvalidConfigurationValue(16))
+    );
+}
+
+#[test]
+fn ambient_lux_resolution_factor() {
+    let mut config = Config::new();
+    assert_eq!(
+        config.set_ambient_lux_resolution_factor(0.0),
+        Err(Error::InvalidConfigurationValue(0))
+    );
+    config
+        .set_ambient_high_interrupt_threshold_lux(20_000.0)
+        .unwrap();
+    assert_eq!(
+        config.set_ambient_lux_resolution_factor(0.3),
+        Err(Error::InvalidConfigurationValue(20_000))
+    );
+    assert_eq!(config.set_ambient_lux_resolution_factor(1.28), Ok(()));
+}
@@ -0,0 +1,244 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    error::Error,
+    mode::{
+        AllowCommunication, AllowReadMeasurement, AllowStartAmbientSingle, AllowStartRangeSingle,
+        ReadyMode,
+    },
+    BusyWait, Config, RangeMeasurement, WaitStrategy, VL6180X,
+};
+
+#[cfg(test)]
+mod detached_tests;
+
+/// VL6180 interface that does not own the I2C bus.
+///
+/// Only the mode, the [Config] (which holds the I2C address) and the [WaitStrategy] are
+/// stored, the bus is passed in to each call, so the bus can be used by other drivers in
+/// between without a shared bus proxy.
+///
+/// ```ignore
+/// let (_, mut tof) = vl6180x::VL6180X::new(&mut i2c).expect("vl").detach();
+/// let range = tof.poll_range_mm_single_blocking(&mut i2c);
+/// let imu_data = imu.read(&mut i2c);
+/// ```
+///
+/// Mode changes and the calls not available here go through
+/// [attach](DetachedVL6180X::attach):
+///
+/// ```ignore
+/// let (_, mut tof) = tof.attach(&mut i2c).start_range_continuous_mode()?.detach();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DetachedVL6180X<MODE, W = BusyWait> {
+    mode: MODE,
+    config: Config,
+    wait: W,
+}
+
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
+where
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
+{
+    /// Split the driver into the I2C bus and a [DetachedVL6180X] in the same mode.
+    pub fn detach(self) -> (I2C, DetachedVL6180X<MODE, W>) {
+        let detached = DetachedVL6180X {
+            mode: self.mode,
+            config: self.config,
+            wait: self.wait,
+        };
+        (self.com, detached)
+    }
+}
+
+impl<MODE, W> DetachedVL6180X<MODE, W>
+where
+    W: WaitStrategy,
+{
+    /// Attach an I2C bus, e.g. `&mut i2c` for as long as the returned driver is used.
+    pub fn attach<I2C: I2c>(self, i2c: I2C) -> VL6180X<MODE, I2C, W> {
+        VL6180X {
+            mode: self.mode,
+            com: i2c,
+            config: self.config,
+            wait: self.wait,
+        }
+    }
+
+    /// The config of the sensor, including its current I2C address.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Driver borrowing `i2c` and the wait strategy for a single call.
+    fn borrow<'a, I2C: I2c>(&'a mut self, i2c: &'a mut I2C) -> VL6180X<MODE, &'a mut I2C, &'a mut W>
+    where
+        MODE: Copy,
+    {
+        VL6180X {
+            mode: self.mode,
+            com: i2c,
+            config: self.config,
+            wait: &mut self.wait,
+        }
+    }
+}
+
+impl<MODE, W> DetachedVL6180X<MODE, W>
+where
+    MODE: AllowReadMeasurement + Copy,
+    W: WaitStrategy,
+{
+    /// Same as [VL6180X::read_range_mm_blocking], borrowing `i2c` for the call.
+    pub fn read_range_mm_blocking<I2C, E>(&mut self, i2c: &mut I2C) -> Result<u16, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).read_range_mm_blocking()
+    }
+
+    /// Same as [VL6180X::read_range_mm], borrowing `i2c` for the call.
+    pub fn read_range_mm<I2C, E>(&mut self, i2c: &mut I2C) -> Result<u16, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).read_range_mm()
+    }
+
+    /// Same as [VL6180X::read_range_detailed_blocking], borrowing `i2c` for the call.
+    pub fn read_range_detailed_blocking<I2C, E>(
+        &mut self,
+        i2c: &mut I2C,
+    ) -> Result<RangeMeasurement, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).read_range_detailed_blocking()
+    }
+
+    /// Same as [VL6180X::read_ambient_lux_blocking], borrowing `i2c` for the call.
+    pub fn read_ambient_lux_blocking<I2C, E>(&mut self, i2c: &mut I2C) -> Result<f32, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).read_ambient_lux_blocking()
+    }
+
+    /// Same as [VL6180X::read_ambient_lux], borrowing `i2c` for the call.
+    pub fn read_ambient_lux<I2C, E>(&mut self, i2c: &mut I2C) -> Result<f32, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).read_ambient_lux()
+    }
+}
+
+impl<MODE, W> DetachedVL6180X<MODE, W>
+where
+    MODE: AllowStartRangeSingle + Copy,
+    W: WaitStrategy,
+{
+    /// Same as [VL6180X::start_range_single], borrowing `i2c` for the call.
+    pub fn start_range_single<I2C, E>(&mut self, i2c: &mut I2C) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).start_range_single()
+    }
+}
+
+impl<MODE, W> DetachedVL6180X<MODE, W>
+where
+    MODE: AllowStartAmbientSingle + Copy,
+    W: WaitStrategy,
+{
+    /// Same as [VL6180X::start_ambient_single], borrowing `i2c` for the call.
+    pub fn start_ambient_single<I2C, E>(&mut self, i2c: &mut I2C) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).start_ambient_single()
+    }
+}
+
+impl<MODE, W> DetachedVL6180X<MODE, W>
+where
+    MODE: AllowCommunication + Copy,
+    W: WaitStrategy,
+{
+    /// Same as [VL6180X::read_model_id], borrowing `i2c` for the call.
+    pub fn read_model_id<I2C, E>(&mut self, i2c: &mut I2C) -> Result<u8, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).read_model_id()
+    }
+
+    /// Same as [VL6180X::read_interrupt_status], borrowing `i2c` for the call.
+    pub fn read_interrupt_status<I2C, E>(&mut self, i2c: &mut I2C) -> Result<u8, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).read_interrupt_status()
+    }
+
+    /// Same as [VL6180X::clear_all_interrupts], borrowing `i2c` for the call.
+    pub fn clear_all_interrupts<I2C, E>(&mut self, i2c: &mut I2C) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).clear_all_interrupts()
+    }
+
+    /// Same as [VL6180X::change_i2c_address], borrowing `i2c` for the call.
+    /// The new address is kept for the following calls.
+    pub fn change_i2c_address<I2C, E>(
+        &mut self,
+        i2c: &mut I2C,
+        new_address: u8,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).change_i2c_address(new_address)?;
+        self.config.address = new_address;
+        Ok(())
+    }
+}
+
+impl<W> DetachedVL6180X<ReadyMode, W>
+where
+    W: WaitStrategy,
+{
+    /// Same as [VL6180X::poll_range_mm_single_blocking], borrowing `i2c` for the call.
+    pub fn poll_range_mm_single_blocking<I2C, E>(&mut self, i2c: &mut I2C) -> Result<u16, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).poll_range_mm_single_blocking()
+    }
+
+    /// Same as [VL6180X::poll_range_detailed_single_blocking], borrowing `i2c` for the call.
+    pub fn poll_range_detailed_single_blocking<I2C, E>(
+        &mut self,
+        i2c: &mut I2C,
+    ) -> Result<RangeMeasurement, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).poll_range_detailed_single_blocking()
+    }
+
+    /// Same as [VL6180X::poll_ambient_lux_single_blocking], borrowing `i2c` for the call.
+    pub fn poll_ambient_lux_single_blocking<I2C, E>(
+        &mut self,
+        i2c: &mut I2C,
+    ) -> Result<f32, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        self.borrow(i2c).poll_ambient_lux_single_blocking()
+    }
+}
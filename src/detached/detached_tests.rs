@@ -0,0 +1,93 @@
+extern crate std;
+
+use super::*;
+use crate::sim::SimBus;
+use crate::{DelayWait, ReadyMode};
+use core::cell::RefCell;
+
+#[test]
+fn borrows_bus_per_call() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let other = bus.add_sensor();
+    sensor.set_range_mm(60);
+    let mut i2c = bus.i2c();
+    let (_, mut tof) = VL6180X::new(&mut i2c).unwrap().detach();
+
+    assert_eq!(tof.poll_range_mm_single_blocking(&mut i2c), Ok(60));
+    tof.change_i2c_address(&mut i2c, 0x30).unwrap();
+    assert_eq!(tof.config().address, 0x30);
+    assert_eq!(sensor.address(), 0x30);
+
+    // The bus is free for the sensor left on the default address.
+    let mut other_tof = VL6180X::new(&mut i2c).unwrap();
+    other.set_range_mm(90);
+    assert_eq!(other_tof.poll_range_mm_single_blocking(), Ok(90));
+    assert_eq!(tof.poll_range_mm_single_blocking(&mut i2c), Ok(60));
+}
+
+#[test]
+fn attach_changes_mode() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(10_000);
+    let mut i2c = bus.i2c();
+    let wait = DelayWait::new(bus.delay());
+    let chip = VL6180X::with_wait_strategy(&mut i2c, &Config::new(), wait).unwrap();
+    let (_, tof) = chip.detach();
+
+    let (_, mut tof) = tof
+        .attach(&mut i2c)
+        .start_range_continuous_mode()
+        .unwrap()
+        .detach();
+    assert_eq!(tof.read_range_mm_blocking(&mut i2c), Ok(100));
+    let (_, mut tof): (_, DetachedVL6180X<ReadyMode, _>) = tof
+        .attach(&mut i2c)
+        .stop_range_continuous_mode()
+        .unwrap()
+        .detach();
+    assert_eq!(tof.poll_range_mm_single_blocking(&mut i2c), Ok(100));
+}
+
+#[test]
+fn single_measurement_per_call() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(40);
+    let mut i2c = bus.i2c();
+    let (_, mut tof) = VL6180X::new(&mut i2c).unwrap().detach();
+
+    assert_eq!(tof.read_model_id(&mut i2c), Ok(0xB4));
+    tof.start_range_single(&mut i2c).unwrap();
+    assert_eq!(tof.read_range_mm_blocking(&mut i2c), Ok(40));
+    tof.clear_all_interrupts(&mut i2c).unwrap();
+    assert_eq!(tof.read_interrupt_status(&mut i2c), Ok(0));
+}
+
+#[test]
+fn shared_bus_ref_cell_device() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let shared = RefCell::new(bus.i2c());
+    let mut tof = VL6180X::new(embedded_hal_bus::i2c::RefCellDevice::new(&shared)).unwrap();
+    sensor.set_range_mm(70);
+    // Another device using the bus in between the driver calls.
+    let mut other = embedded_hal_bus::i2c::RefCellDevice::new(&shared);
+    assert!(I2c::write(&mut other, 0x50, &[0]).is_err());
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(70));
+}
+
+#[test]
+fn shared_bus_critical_section_device() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let shared = critical_section::Mutex::new(RefCell::new(bus.i2c()));
+    let mut tof = VL6180X::new(embedded_hal_bus::i2c::CriticalSectionDevice::new(&shared)).unwrap();
+    sensor.set_range_mm(70);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(70));
+}
+
+#[test]
+fn shared_bus_mutex_device() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let shared = std::sync::Mutex::new(bus.i2c());
+    let mut tof = VL6180X::new(embedded_hal_bus::i2c::MutexDevice::new(&shared)).unwrap();
+    sensor.set_range_mm(70);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(70));
+}
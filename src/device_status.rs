@@ -1,25 +1,73 @@
 use super::VL6180X;
 use crate::{
     error::{Error, Error2},
-    register::{Register8Bit::*, SysInterruptClearCode},
-};
-use embedded_hal::{
-    blocking::i2c::{Write, WriteRead},
-    digital::v2::OutputPin,
+    register::{
+        Register16Bit, Register8Bit, Register8Bit::*, SysHistoryCtrlCode, SysInterruptClearCode,
+    },
+    WaitStrategy,
 };
+use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
+
+#[cfg(test)]
+mod device_status_tests;
+
+/// Time the device needs to boot after the x shutdown pin is raised,
+/// see the VL6180X datasheet power up and boot sequence.
+pub(crate) const BOOT_TIME_US: u32 = 1000;
+/// Time waited between two reads of SYSTEM__FRESH_OUT_OF_RESET while the device boots.
+pub(crate) const BOOT_POLL_INTERVAL_US: u32 = 100;
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+/// Length of the block of identification registers, from `IDENTIFICATION__MODEL_ID` (0x000)
+/// up to and including `IDENTIFICATION__TIME` (0x008 - 0x009).
+pub(crate) const DEVICE_INFO_LEN: usize = 0x00A;
+
+/// Identification and revision of the sensor, as programmed at manufacture.
+///
+/// See VL6180X datasheet section 6.2 IDENTIFICATION__* registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Model id, 0xB4 for the VL6180X (IDENTIFICATION__MODEL_ID).
+    pub model_id: u8,
+    /// Silicon model major revision (IDENTIFICATION__MODEL_REV_MAJOR).
+    pub model_rev_major: u8,
+    /// Silicon model minor revision (IDENTIFICATION__MODEL_REV_MINOR).
+    pub model_rev_minor: u8,
+    /// Module major revision (IDENTIFICATION__MODULE_REV_MAJOR).
+    pub module_rev_major: u8,
+    /// Module minor revision (IDENTIFICATION__MODULE_REV_MINOR).
+    pub module_rev_minor: u8,
+    /// Last digit of the manufacturing year (IDENTIFICATION__DATE_HI bits 7:4).
+    pub manufacture_year: u8,
+    /// Manufacturing month (IDENTIFICATION__DATE_HI bits 3:0).
+    pub manufacture_month: u8,
+    /// Manufacturing day (IDENTIFICATION__DATE_LO bits 7:3).
+    pub manufacture_day: u8,
+    /// Manufacturing phase (IDENTIFICATION__DATE_LO bits 2:0).
+    pub manufacture_phase: u8,
+    /// Manufacturing time in seconds since midnight (IDENTIFICATION__TIME).
+    pub manufacture_time_s: u32,
+}
+
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     pub(crate) fn read_model_id_direct(&mut self) -> Result<u8, Error<E>> {
         let id = self.read_named_register(IDENTIFICATION__MODEL_ID)?;
         Ok(id)
     }
 
+    /// Reads all identification registers in one transfer.
+    pub(crate) fn read_device_info_direct(&mut self) -> Result<DeviceInfo, Error<E>> {
+        let mut info = [0u8; DEVICE_INFO_LEN];
+        self.read_bytes(IDENTIFICATION__MODEL_ID as u16, &mut info)?;
+        Ok(parse_device_info(&info))
+    }
+
     pub(crate) fn read_interrupt_status_direct(&mut self) -> Result<u8, Error<E>> {
         let status = self.read_named_register(RESULT__INTERRUPT_STATUS_GPIO)?;
-        return Ok(status);
+        Ok(status)
     }
 
     pub(crate) fn clear_error_interrupt_direct(&mut self) -> Result<(), Error<E>> {
@@ -46,15 +94,23 @@ where
         Ok(())
     }
 
+    pub(crate) fn clear_history_buffer_direct(&mut self) -> Result<(), Error<E>> {
+        self.write_named_register(
+            SYSTEM__HISTORY_CTRL,
+            self.config.history_buffer_mode as u8 | SysHistoryCtrlCode::Clear as u8,
+        )?;
+        Ok(())
+    }
+
     fn clear_interrupt(&mut self, code: u8) -> Result<(), E> {
         self.write_named_register(SYSTEM__INTERRUPT_CLEAR, code)
     }
 
     pub(crate) fn change_i2c_address_direct(&mut self, new_address: u8) -> Result<(), Error<E>> {
-        if new_address < 0x08 || new_address > 0x77 {
+        if !(0x08..=0x77).contains(&new_address) {
             return Err(Error::InvalidAddress(new_address));
         }
-        self.write_only_named_register(I2C_SLAVE__DEVICE_ADDRESS, new_address)?;
+        self.write_named_register(I2C_SLAVE__DEVICE_ADDRESS, new_address)?;
         self.config.address = new_address;
 
         Ok(())
@@ -67,31 +123,59 @@ where
         x_shutdown_pin.set_low().map_err(|e| Error::GpioPinError(e))
     }
 
-    pub(crate) fn power_on_and_init_direct<PE, P: OutputPin<Error = PE>>(
+    pub(crate) fn power_on_and_init_direct<PE, P: OutputPin<Error = PE>, D: DelayNs>(
         &mut self,
         x_shutdown_pin: &mut P,
+        delay: &mut D,
     ) -> Result<(), Error2<E, PE>> {
         x_shutdown_pin
             .set_high()
             .map_err(|e| Error2::GpioPinError(e))?;
-        self.wait_device_booted()
-            .map_err(|e| Error2::<E, PE>::BusError(e))?;
+        self.wait_device_booted(delay)?;
         self.init_hardware()
             .map_err(|e| Error2::<E, PE>::BusError(e))?;
         Ok(())
     }
 
-    fn wait_device_booted(&mut self) -> Result<(), E> {
+    /// Waits for SYSTEM__FRESH_OUT_OF_RESET after the x shutdown pin has been raised.
+    /// Bus errors are expected while the device boots, the last one is returned
+    /// with [Error::BootTimeout] if the device has not booted before the deadline.
+    pub(crate) fn wait_device_booted<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<E>> {
+        delay.delay_us(BOOT_TIME_US);
+        let mut waited_us: u32 = 0;
         loop {
-            match self.read_named_register(SYSTEM__FRESH_OUT_OF_RESET) {
-                Ok(result) => {
-                    if result == 0x01 {
-                        break;
-                    }
-                }
-                Err(_) => (),
+            let last_error = match self.read_named_register(SYSTEM__FRESH_OUT_OF_RESET) {
+                Ok(0x01) => return Ok(()),
+                Ok(_) => None,
+                Err(e) => Some(e),
+            };
+            if waited_us >= self.config.boot_timeout_us {
+                return Err(Error::BootTimeout(last_error));
             }
+            delay.delay_us(BOOT_POLL_INTERVAL_US);
+            waited_us = waited_us.saturating_add(BOOT_POLL_INTERVAL_US);
         }
-        Ok(())
+    }
+}
+
+pub(crate) fn parse_device_info(info: &[u8; DEVICE_INFO_LEN]) -> DeviceInfo {
+    let u8_at = |reg: Register8Bit| info[reg as usize];
+    let date_hi = u8_at(IDENTIFICATION__DATE_HI);
+    let date_lo = u8_at(IDENTIFICATION__DATE_LO);
+    let time_at = Register16Bit::IDENTIFICATION__TIME as usize;
+    let time = u16::from_be_bytes([info[time_at], info[time_at + 1]]);
+
+    DeviceInfo {
+        model_id: u8_at(IDENTIFICATION__MODEL_ID),
+        model_rev_major: u8_at(IDENTIFICATION__MODEL_REV_MAJOR) & 0b111,
+        model_rev_minor: u8_at(IDENTIFICATION__MODEL_REV_MINOR) & 0b111,
+        module_rev_major: u8_at(IDENTIFICATION__MODULE_REV_MAJOR) & 0b111,
+        module_rev_minor: u8_at(IDENTIFICATION__MODULE_REV_MINOR) & 0b111,
+        manufacture_year: date_hi >> 4,
+        manufacture_month: date_hi & 0x0F,
+        manufacture_day: date_lo >> 3,
+        manufacture_phase: date_lo & 0b111,
+        // The register counts in units of 2 seconds
+        manufacture_time_s: time as u32 * 2,
     }
 }
@@ -0,0 +1,166 @@
+use super::*;
+use crate::{mode::OperatingMode, sim::SimBus, Config};
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+#[test]
+fn read_device_info() {
+    let (bus, _sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(
+        tof.read_device_info(),
+        Ok(DeviceInfo {
+            model_id: 0xB4,
+            model_rev_major: 1,
+            model_rev_minor: 3,
+            module_rev_major: 1,
+            module_rev_minor: 2,
+            manufacture_year: 2,
+            manufacture_month: 6,
+            manufacture_day: 15,
+            manufacture_phase: 2,
+            manufacture_time_s: 49510,
+        })
+    );
+}
+
+#[test]
+fn device_info_ignores_reserved_revision_bits() {
+    let mut info = [0u8; DEVICE_INFO_LEN];
+    info[IDENTIFICATION__MODEL_REV_MAJOR as usize] = 0xF9;
+    info[IDENTIFICATION__MODULE_REV_MINOR as usize] = 0x0A;
+    info[IDENTIFICATION__DATE_HI as usize] = 0x9C;
+    info[IDENTIFICATION__DATE_LO as usize] = 0xFF;
+    let info = parse_device_info(&info);
+    assert_eq!(info.model_rev_major, 1);
+    assert_eq!(info.module_rev_minor, 2);
+    assert_eq!(info.manufacture_year, 9);
+    assert_eq!(info.manufacture_month, 12);
+    assert_eq!(info.manufacture_day, 31);
+    assert_eq!(info.manufacture_phase, 7);
+}
+
+#[test]
+fn try_read_device_info_powered_off() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap().into_dynamic_mode();
+    assert_eq!(
+        tof.try_read_device_info().map(|info| info.model_id),
+        Ok(0xB4)
+    );
+    tof.try_power_off(&mut x_shutdown_pin).unwrap();
+    assert_eq!(
+        tof.try_read_device_info(),
+        Err(Error::InvalidMethod(OperatingMode::PoweredOff))
+    );
+}
+
+#[test]
+fn power_on_waits_for_boot() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_boot_time_us(3_000);
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let tof = VL6180X::new(bus.i2c()).unwrap();
+    let tof = tof.power_off(&mut x_shutdown_pin).unwrap();
+    let start_us = bus.now_us();
+    let mut tof = tof
+        .power_on_and_init(&mut x_shutdown_pin, &mut bus.delay())
+        .unwrap();
+    assert!(bus.now_us() - start_us >= 3_000);
+    assert_eq!(tof.read_model_id(), Ok(0xB4));
+}
+
+#[test]
+fn power_on_boot_timeout() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_boot_time_us(20_000);
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let mut config = Config::new();
+    config.set_boot_timeout_us(5_000);
+    let tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    let tof = tof.power_off(&mut x_shutdown_pin).unwrap();
+    let start_us = bus.now_us();
+    assert_eq!(
+        tof.power_on_and_init(&mut x_shutdown_pin, &mut bus.delay())
+            .err()
+            .unwrap(),
+        Error2::DriverError(Error::BootTimeout(Some(ErrorKind::NoAcknowledge(
+            NoAcknowledgeSource::Address
+        ))))
+    );
+    assert_eq!(bus.now_us() - start_us, BOOT_TIME_US as u64 + 5_000);
+}
+
+#[test]
+fn try_power_on_boot_timeout_stays_powered_off() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap().into_dynamic_mode();
+    tof.try_power_off(&mut x_shutdown_pin).unwrap();
+    sensor.set_boot_time_us(100_000);
+    assert!(matches!(
+        tof.try_power_on_and_init(&mut x_shutdown_pin, &mut bus.delay()),
+        Err(Error2::DriverError(Error::BootTimeout(Some(_))))
+    ));
+    assert_eq!(
+        tof.try_read_device_info(),
+        Err(Error::InvalidMethod(OperatingMode::PoweredOff))
+    );
+}
+
+#[test]
+fn release_and_rebuild_powered_off() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let mut config = Config::new();
+    config.set_range_max_convergence_time(20).unwrap();
+    let tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    let i2c = tof.power_off(&mut x_shutdown_pin).unwrap().release();
+
+    bus.set_transaction_time_us(1_000);
+    let start_us = bus.now_us();
+    let tof = VL6180X::new_powered_off(i2c, &config);
+    assert_eq!(bus.now_us(), start_us);
+    assert!(!sensor.is_powered());
+
+    tof.power_on_and_init(&mut x_shutdown_pin, &mut bus.delay())
+        .unwrap();
+    assert_eq!(sensor.register(SYSRANGE__MAX_CONVERGENCE_TIME as u16), 20);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_power_on_boot_timeout() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    embassy_futures::block_on(async {
+        let tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        let tof = tof.power_off(&mut x_shutdown_pin).unwrap();
+        sensor.set_boot_time_us(100_000);
+        assert!(matches!(
+            tof.power_on_and_init(&mut x_shutdown_pin).await,
+            Err(Error2::DriverError(Error::BootTimeout(Some(_))))
+        ));
+    });
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_release_and_rebuild_powered_off() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    embassy_futures::block_on(async {
+        let tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        let (i2c, delay) = tof
+            .power_off(&mut x_shutdown_pin)
+            .unwrap()
+            .release_with_delay();
+        let tof = crate::asynch::VL6180X::new_powered_off(i2c, delay, &Config::new());
+        let mut tof = tof.power_on_and_init(&mut x_shutdown_pin).await.unwrap();
+        assert_eq!(tof.read_model_id().await, Ok(0xB4));
+    });
+}
@@ -9,12 +9,19 @@ pub enum Error<E> {
     BusError(E),
     /// Timeout.
     Timeout,
+    /// The device did not boot before the
+    /// [boot timeout](crate::Config::set_boot_timeout_us) after powering on.
+    /// Carries the last bus error, `None` if the device answered but was not yet booted.
+    BootTimeout(Option<E>),
     /// I2C address not valid, needs to be between 0x08 and 0x77.
     /// It is a 7 bit address thus the range is 0x00 - 0x7F but
     /// 0x00 - 0x07 and 0x78 - 0x7F are reserved I2C addresses and cannot be used.
     InvalidAddress(u8),
     /// Invalid configuration value.
     InvalidConfigurationValue(u16),
+    /// Neither GPIO pin is configured as interrupt output, see
+    /// [set_gpio1_function](crate::Config::set_gpio1_function).
+    NoInterruptOutput,
     /// The measurement reading is not ready.
     ResultNotReady,
     /// Error reading the range measurement.
@@ -37,6 +44,17 @@ pub enum Error2<E, F> {
     InvalidMethod(mode::dynamic::OperatingMode),
     /// Error when setting pin output state.
     GpioPinError(F),
+    /// Any other driver error, see [Error].
+    DriverError(Error<E>),
+}
+
+/// Error of a single sensor of a [SensorArray](crate::SensorArray).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SensorArrayError<E> {
+    /// Index of the sensor that failed, in the order the array was created with.
+    pub index: usize,
+    /// The error of that sensor.
+    pub error: E,
 }
 
 impl<E, F> From<E> for Error2<E, F> {
@@ -45,8 +63,28 @@ impl<E, F> From<E> for Error2<E, F> {
     }
 }
 
+impl<E, F> From<Error<E>> for Error2<E, F> {
+    fn from(error: Error<E>) -> Self {
+        match error {
+            Error::BusError(e) => Error2::BusError(e),
+            other => Error2::DriverError(other),
+        }
+    }
+}
+
 impl<E> From<E> for Error<E> {
     fn from(error: E) -> Self {
         Error::BusError(error)
     }
 }
+
+impl<E: embedded_hal::i2c::Error> embedded_hal::i2c::Error for Error<E> {
+    /// Bus errors report the [ErrorKind](embedded_hal::i2c::ErrorKind) of the underlying
+    /// I2C error, all other driver errors are reported as `Other`.
+    fn kind(&self) -> embedded_hal::i2c::ErrorKind {
+        match self {
+            Error::BusError(e) => e.kind(),
+            _ => embedded_hal::i2c::ErrorKind::Other,
+        }
+    }
+}
@@ -1,9 +1,12 @@
 use super::*;
-use crate::register::{Register16Bit, Register8Bit};
+use crate::register::{Register16Bit, Register32Bit, Register8Bit};
+use crate::WaitStrategy;
+use embedded_hal::i2c::Operation;
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     /// Reads a named 8-bit register
     pub(crate) fn read_named_register(&mut self, reg: Register8Bit) -> Result<u8, E> {
@@ -13,9 +16,7 @@ where
     /// Reads an 8-bit register
     fn read_register(&mut self, reg: u16) -> Result<u8, E> {
         let mut data: [u8; 1] = [0];
-        let reg: [u8; 2] = reg.to_be_bytes();
-
-        self.com.write_read(self.config.address, &reg, &mut data)?;
+        self.read_bytes(reg, &mut data)?;
         Ok(data[0])
     }
 
@@ -27,38 +28,26 @@ where
     /// Reads a 16-bit register
     fn read_register_16bit(&mut self, reg: u16) -> Result<u16, E> {
         let mut data: [u8; 2] = [0, 0];
-        let reg: [u8; 2] = reg.to_be_bytes();
-
-        self.com.write_read(self.config.address, &reg, &mut data)?;
+        self.read_bytes(reg, &mut data)?;
         Ok(u16::from_be_bytes(data))
     }
 
     /// Reads a named 32-bit register
-    fn read_named_register_32bit(&mut self, reg: Register16Bit) -> Result<u32, E> {
+    pub(crate) fn read_named_register_32bit(&mut self, reg: Register32Bit) -> Result<u32, E> {
         self.read_register_32bit(reg as u16)
     }
 
     /// Reads a 32-bit register
     fn read_register_32bit(&mut self, reg: u16) -> Result<u32, E> {
         let mut data: [u8; 4] = [0, 0, 0, 0];
-        let reg: [u8; 2] = reg.to_be_bytes();
-
-        self.com.write_read(self.config.address, &reg, &mut data)?;
+        self.read_bytes(reg, &mut data)?;
         Ok(u32::from_be_bytes(data))
     }
 
-    pub(super) fn write_only_named_register(
-        &mut self,
-        reg: Register8Bit,
-        code: u8,
-    ) -> Result<(), E> {
-        self.write_only_register(reg as u16, code)
-    }
-
-    pub(super) fn write_only_register(&mut self, reg: u16, code: u8) -> Result<(), E> {
-        let reg = reg.to_be_bytes();
-        let bytes: [u8; 3] = [reg[0], reg[1], code];
-        self.com.write(self.config.address, &bytes)
+    /// Reads `data.len()` consecutive bytes starting at `reg`
+    pub(crate) fn read_bytes(&mut self, reg: u16, data: &mut [u8]) -> Result<(), E> {
+        let reg: [u8; 2] = reg.to_be_bytes();
+        self.com.write_read(self.config.address, &reg, data)
     }
 
     pub(super) fn write_named_register(&mut self, reg: Register8Bit, code: u8) -> Result<(), E> {
@@ -66,11 +55,7 @@ where
     }
 
     pub(super) fn write_register(&mut self, reg: u16, code: u8) -> Result<(), E> {
-        let mut buffer = [0];
-        let reg = reg.to_be_bytes();
-        let bytes: [u8; 3] = [reg[0], reg[1], code];
-        self.com
-            .write_read(self.config.address, &bytes, &mut buffer)
+        self.write_bytes(reg, &[code])
     }
 
     pub(super) fn write_named_register_16bit(
@@ -82,39 +67,27 @@ where
     }
 
     fn write_register_16bit(&mut self, reg: u16, code: u16) -> Result<(), E> {
-        let mut buffer = [0];
-        let code = code.to_be_bytes();
-        let reg = reg.to_be_bytes();
-        let bytes: [u8; 4] = [reg[0], reg[1], code[0], code[1]];
-        self.com
-            .write_read(self.config.address, &bytes, &mut buffer)
+        self.write_bytes(reg, &code.to_be_bytes())
     }
 
     pub(super) fn write_named_register_32bit(
         &mut self,
-        reg: Register16Bit,
+        reg: Register32Bit,
         code: u32,
     ) -> Result<(), E> {
-        self.write_register_32bit(reg as u32, code)
+        self.write_register_32bit(reg as u16, code)
     }
 
-    fn write_register_32bit(&mut self, reg: u32, code: u32) -> Result<(), E> {
-        let mut buffer = [0];
-        let code = code.to_be_bytes();
-        let reg = reg.to_be_bytes();
-        let bytes: [u8; 6] = [reg[0], reg[1], code[0], code[1], code[2], code[3]];
-        self.com
-            .write_read(self.config.address, &bytes, &mut buffer)
+    fn write_register_32bit(&mut self, reg: u16, code: u32) -> Result<(), E> {
+        self.write_bytes(reg, &code.to_be_bytes())
     }
 
-    // fn write_6bytes(&mut self, reg: Register8Bit, bytes: [u8; 6]) -> Result<(), E> {
-    //     let mut buf: [u8; 6] = [0, 0, 0, 0, 0, 0];
-    //     self.com.write_read(
-    //         self.config.address,
-    //         &[
-    //             reg as u8, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5],
-    //         ],
-    //         &mut buf,
-    //     )
-    // }
+    /// Writes `data` to consecutive registers starting at `reg` in a single transaction
+    pub(crate) fn write_bytes(&mut self, reg: u16, data: &[u8]) -> Result<(), E> {
+        let reg: [u8; 2] = reg.to_be_bytes();
+        self.com.transaction(
+            self.config.address,
+            &mut [Operation::Write(&reg), Operation::Write(data)],
+        )
+    }
 }
@@ -1,50 +1,184 @@
 use super::VL6180X;
 use crate::register::{
-    Register16Bit::*, Register8Bit::*, SysModeGpio1Polarity, SysModeGpio1Select,
-    AMBIENT_ANALOGUE_GAIN_CODE, RANGE_SCALAR_CODE,
+    Register16Bit::*, Register8Bit::*, AMBIENT_ANALOGUE_GAIN_CODE, RANGE_SCALAR_CODE,
 };
-use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::{error::Error, Config, WaitStrategy};
+use embedded_hal::i2c::I2c;
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+#[cfg(test)]
+mod init_tests;
+
+/// Private register settings from ST application note AN4545,
+/// section "SR03 settings" - "Mandatory : private registers"
+pub(crate) const SR03_SETTINGS: [(u16, u8); 30] = [
+    (0x207, 0x01),
+    (0x208, 0x01),
+    (0x096, 0x00),
+    (0x097, 0xFD), // RANGE_SCALER = 253
+    (0x0E3, 0x01),
+    (0x0E4, 0x03),
+    (0x0E5, 0x02),
+    (0x0E6, 0x01),
+    (0x0E7, 0x03),
+    (0x0F5, 0x02),
+    (0x0D9, 0x05),
+    (0x0DB, 0xCE),
+    (0x0DC, 0x03),
+    (0x0DD, 0xF8),
+    (0x09F, 0x00),
+    (0x0A3, 0x3C),
+    (0x0B7, 0x00),
+    (0x0BB, 0x3C),
+    (0x0B2, 0x09),
+    (0x0CA, 0x09),
+    (0x198, 0x01),
+    (0x1B0, 0x17),
+    (0x1AD, 0x00),
+    (0x0FF, 0x05),
+    (0x100, 0x05),
+    (0x199, 0x05),
+    (0x1A6, 0x1B),
+    (0x1AC, 0x3E),
+    (0x1A7, 0x1F),
+    (0x030, 0x00),
+];
+
+/// A register value derived from the [Config], see [config_registers].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ConfigRegister {
+    Byte(u16, u8),
+    Word(u16, u16),
+}
+
+impl ConfigRegister {
+    /// Interrupt configuration, thresholds and ambient light gain and integration period,
+    /// which the sensor applies together when SYSTEM__GROUPED_PARAMETER_HOLD is released.
+    pub(crate) fn is_grouped(&self) -> bool {
+        let (ConfigRegister::Byte(reg, _) | ConfigRegister::Word(reg, _)) = *self;
+        [
+            SYSTEM__INTERRUPT_CONFIG_GPIO as u16,
+            SYSALS__ANALOGUE_GAIN as u16,
+            SYSALS__INTEGRATION_PERIOD as u16,
+            SYSRANGE__THRESH_HIGH as u16,
+            SYSRANGE__THRESH_LOW as u16,
+            SYSALS__THRESH_HIGH as u16,
+            SYSALS__THRESH_LOW as u16,
+        ]
+        .contains(&reg)
+    }
+}
+
+/// The registers set from `config` with their values, in the order they are written by
+/// `init_hardware`. The range scaling registers are set separately, as the part-to-part
+/// offset and crosstalk valid height depend on the scaling.
+pub(crate) fn config_registers(config: &Config) -> [ConfigRegister; 22] {
+    use ConfigRegister::{Byte, Word};
+    [
+        Byte(
+            READOUT__AVERAGING_SAMPLE_PERIOD as u16,
+            config.readout_averaging_period_multiplier,
+        ),
+        Byte(
+            SYSALS__ANALOGUE_GAIN as u16,
+            AMBIENT_ANALOGUE_GAIN_CODE[config.ambient_analogue_gain_level as usize],
+        ),
+        Byte(FIRMWARE__RESULT_SCALER as u16, config.ambient_scaling),
+        Byte(
+            SYSRANGE__VHV_REPEAT_RATE as u16,
+            config.range_vhv_recalibration_rate,
+        ),
+        Word(
+            SYSALS__INTEGRATION_PERIOD as u16,
+            config.ambient_integration_period - 1,
+        ),
+        Byte(
+            SYSALS__INTERMEASUREMENT_PERIOD as u16,
+            ((config.ambient_inter_measurement_period / 10) as u8) - 1,
+        ),
+        Byte(
+            SYSRANGE__INTERMEASUREMENT_PERIOD as u16,
+            ((config.range_inter_measurement_period / 10) as u8) - 1,
+        ),
+        Byte(
+            SYSTEM__INTERRUPT_CONFIG_GPIO as u16,
+            config.interrupt_config(),
+        ),
+        // Interrupt outputs are disabled without interrupts
+        Byte(SYSTEM__MODE_GPIO0 as u16, config.gpio0_mode()),
+        Byte(SYSTEM__MODE_GPIO1 as u16, config.gpio1_mode()),
+        Byte(
+            SYSRANGE__THRESH_HIGH as u16,
+            config
+                .range_high_interrupt_threshold
+                .code(config.range_scaling),
+        ),
+        Byte(
+            SYSRANGE__THRESH_LOW as u16,
+            config
+                .range_low_interrupt_threshold
+                .code(config.range_scaling),
+        ),
+        Word(
+            SYSALS__THRESH_HIGH as u16,
+            config.ambient_high_interrupt_threshold.code(config),
+        ),
+        Word(
+            SYSALS__THRESH_LOW as u16,
+            config.ambient_low_interrupt_threshold.code(config),
+        ),
+        Byte(
+            SYSRANGE__MAX_CONVERGENCE_TIME as u16,
+            config.range_max_convergence_time,
+        ),
+        Word(
+            SYSRANGE__CROSSTALK_COMPENSATION_RATE as u16,
+            config.range_crosstalk_compensation_rate,
+        ),
+        Word(
+            SYSRANGE__RANGE_IGNORE_THRESHOLD as u16,
+            config.range_ignore.threshold,
+        ),
+        Byte(
+            SYSRANGE__RANGE_IGNORE_VALID_HEIGHT as u16,
+            config.range_ignore.valid_height_code(config.range_scaling),
+        ),
+        Word(
+            SYSRANGE__EARLY_CONVERGENCE_ESTIMATE as u16,
+            config.range_checks.early_convergence_estimate_threshold,
+        ),
+        Byte(
+            SYSRANGE__MAX_AMBIENT_LEVEL_MULT as u16,
+            config.range_checks.max_ambient_level_multiplier,
+        ),
+        Byte(
+            SYSRANGE__RANGE_CHECK_ENABLES as u16,
+            config.range_check_enables(),
+        ),
+        Byte(
+            SYSTEM__HISTORY_CTRL as u16,
+            config.history_buffer_mode as u8,
+        ),
+    ]
+}
+
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     /// Initialize sensor with settings from ST application note AN4545,
     /// section "SR03 settings" - "Mandatory : private registers"
     pub(crate) fn init_hardware(&mut self) -> Result<(), E> {
-        // Store part-to-part range offset so it can be adjusted if scaling is changed
-        self.config.ptp_offset = self.read_named_register(SYSRANGE__PART_TO_PART_RANGE_OFFSET)?;
-
-        self.write_register(0x207, 0x01)?;
-        self.write_register(0x208, 0x01)?;
-        self.write_register(0x096, 0x00)?;
-        self.write_register(0x097, 0xFD)?; // RANGE_SCALER = 253
-        self.write_register(0x0E3, 0x01)?;
-        self.write_register(0x0E4, 0x03)?;
-        self.write_register(0x0E5, 0x02)?;
-        self.write_register(0x0E6, 0x01)?;
-        self.write_register(0x0E7, 0x03)?;
-        self.write_register(0x0F5, 0x02)?;
-        self.write_register(0x0D9, 0x05)?;
-        self.write_register(0x0DB, 0xCE)?;
-        self.write_register(0x0DC, 0x03)?;
-        self.write_register(0x0DD, 0xF8)?;
-        self.write_register(0x09F, 0x00)?;
-        self.write_register(0x0A3, 0x3C)?;
-        self.write_register(0x0B7, 0x00)?;
-        self.write_register(0x0BB, 0x3C)?;
-        self.write_register(0x0B2, 0x09)?;
-        self.write_register(0x0CA, 0x09)?;
-        self.write_register(0x198, 0x01)?;
-        self.write_register(0x1B0, 0x17)?;
-        self.write_register(0x1AD, 0x00)?;
-        self.write_register(0x0FF, 0x05)?;
-        self.write_register(0x100, 0x05)?;
-        self.write_register(0x199, 0x05)?;
-        self.write_register(0x1A6, 0x1B)?;
-        self.write_register(0x1AC, 0x3E)?;
-        self.write_register(0x1A7, 0x1F)?;
-        self.write_register(0x030, 0x00)?;
+        // Store part-to-part range offset so it can be adjusted if scaling is changed,
+        // unless a calibrated offset has been configured
+        self.config.ptp_offset = match self.config.range_offset_mm {
+            Some(offset) => offset,
+            None => self.read_named_register(SYSRANGE__PART_TO_PART_RANGE_OFFSET)? as i8,
+        };
+
+        for (reg, code) in SR03_SETTINGS.iter() {
+            self.write_register(*reg, *code)?;
+        }
 
         self.write_named_register(SYSTEM__FRESH_OUT_OF_RESET, 0)?;
 
@@ -56,50 +190,13 @@ where
     /// See VL6180X datasheet and application note to understand how the config
     /// values get transformed into the values the registers are set to.
     fn set_configuration(&mut self) -> Result<(), E> {
-        self.write_named_register(
-            READOUT__AVERAGING_SAMPLE_PERIOD,
-            self.config.readout_averaging_period_multiplier,
-        )?;
-
-        self.write_named_register(
-            SYSALS__ANALOGUE_GAIN,
-            AMBIENT_ANALOGUE_GAIN_CODE[self.config.ambient_analogue_gain_level as usize],
-        )?;
-
-        self.write_named_register(FIRMWARE__RESULT_SCALER, self.config.ambient_scaling)?;
-
-        self.write_named_register(
-            SYSRANGE__VHV_REPEAT_RATE,
-            self.config.range_vhv_recalibration_rate,
-        )?;
-
-        let integration_period_val = self.config.ambient_integration_period - 1;
-        self.write_named_register_16bit(SYSALS__INTEGRATION_PERIOD, integration_period_val)?;
-
-        let ambient_inter_measurement_val =
-            ((self.config.ambient_inter_measurement_period / 10) as u8) - 1;
-        self.write_named_register(
-            SYSALS__INTERMEASUREMENT_PERIOD,
-            ambient_inter_measurement_val,
-        )?;
+        for register in config_registers(&self.config).iter() {
+            self.write_config_register(*register)?;
+        }
 
         // Manually trigger a range VHV recalibration
         self.write_named_register(SYSRANGE__VHV_RECALIBRATE, 0x01)?;
 
-        let range_inter_measurement_val =
-            ((self.config.range_inter_measurement_period / 10) as u8) - 1;
-        self.write_named_register(
-            SYSRANGE__INTERMEASUREMENT_PERIOD,
-            range_inter_measurement_val,
-        )?;
-
-        self.set_interrupts()?;
-
-        self.write_named_register(
-            SYSRANGE__MAX_CONVERGENCE_TIME,
-            self.config.range_max_convergence_time,
-        )?;
-
         // disable interleaved mode
         self.write_named_register(INTERLEAVED_MODE__ENABLE, 0)?;
 
@@ -108,45 +205,78 @@ where
         Ok(())
     }
 
-    fn set_interrupts(&mut self) -> Result<(), E> {
-        // Set the interrupt mode
-        let interrupt_val =
-            self.config.range_interrupt_mode as u8 | self.config.ambient_interrupt_mode as u8;
-        self.write_named_register(SYSTEM__INTERRUPT_CONFIG_GPIO, interrupt_val)?;
-
-        // Enable or disable GPIO1 as interrupt output
-        if interrupt_val != 0x00 {
-            self.write_named_register(
-                SYSTEM__MODE_GPIO1,
-                SysModeGpio1Polarity::ActiveHigh as u8 | SysModeGpio1Select::InterruptOutput as u8,
-            )?;
-        } else {
-            self.write_named_register(
-                SYSTEM__MODE_GPIO1,
-                SysModeGpio1Polarity::ActiveHigh as u8 | SysModeGpio1Select::Off as u8,
-            )?;
+    fn write_config_register(&mut self, register: ConfigRegister) -> Result<(), E> {
+        match register {
+            ConfigRegister::Byte(reg, code) => self.write_register(reg, code),
+            ConfigRegister::Word(reg, code) => self.write_bytes(reg, &code.to_be_bytes()),
         }
+    }
 
-        // Set the thresholds
-        self.write_named_register(
-            SYSRANGE__THRESH_HIGH,
-            self.config.range_high_interrupt_threshold,
-        )?;
-        self.write_named_register(
-            SYSRANGE__THRESH_LOW,
-            self.config.range_low_interrupt_threshold,
-        )?;
-        self.write_named_register_16bit(
-            SYSALS__THRESH_HIGH,
-            self.config.ambient_high_interrupt_threshold,
-        )?;
-        self.write_named_register_16bit(
-            SYSALS__THRESH_LOW,
-            self.config.ambient_low_interrupt_threshold,
-        )?;
+    /// Writes the registers that differ between the stored config and `config`, then
+    /// stores `config`. Changed interrupt registers are written within a grouped
+    /// parameter hold so the sensor picks them up together.
+    pub(crate) fn write_changed_registers(&mut self, config: &Config) -> Result<(), E> {
+        let old = config_registers(&self.config);
+        let new = config_registers(config);
+        let changed = || old.iter().zip(new.iter()).filter(|(o, n)| o != n);
+
+        if changed().any(|(_, n)| n.is_grouped()) {
+            self.write_named_register(SYSTEM__GROUPED_PARAMETER_HOLD, 0x01)?;
+            for (_, register) in changed().filter(|(_, n)| n.is_grouped()) {
+                self.write_config_register(*register)?;
+            }
+            self.write_named_register(SYSTEM__GROUPED_PARAMETER_HOLD, 0x00)?;
+        }
+        for (_, register) in changed().filter(|(_, n)| !n.is_grouped()) {
+            self.write_config_register(*register)?;
+        }
 
+        let scaling_changed = config.range_scaling != self.config.range_scaling
+            || config.ptp_offset != self.config.ptp_offset;
+        let address = self.config.address;
+        self.config = *config;
+        self.config.address = address;
+        if scaling_changed {
+            self.set_range_scaling(self.config.range_scaling)?;
+        }
         Ok(())
     }
+
+    pub(crate) fn apply_config_direct(&mut self, config: &Config) -> Result<(), Error<E>> {
+        config.validate()?;
+        let mut config = *config;
+        config.ptp_offset = config.range_offset_mm.unwrap_or(self.config.ptp_offset);
+        self.write_changed_registers(&config)?;
+        if config.address != self.config.address {
+            self.change_i2c_address_direct(config.address)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn apply_interrupt_thresholds_direct(&mut self, config: &Config) -> Result<(), E> {
+        let mut new_config = self.config;
+        new_config.copy_interrupt_thresholds(config);
+        self.write_changed_registers(&new_config)
+    }
+
+    pub(crate) fn set_range_thresholds_mm_direct(
+        &mut self,
+        low_mm: u16,
+        high_mm: u16,
+    ) -> Result<(), Error<E>> {
+        let config = self.config.with_range_thresholds_mm(low_mm, high_mm)?;
+        Ok(self.write_changed_registers(&config)?)
+    }
+
+    pub(crate) fn set_ambient_thresholds_direct(
+        &mut self,
+        low: u16,
+        high: u16,
+    ) -> Result<(), Error<E>> {
+        let config = self.config.with_ambient_thresholds(low, high)?;
+        Ok(self.write_changed_registers(&config)?)
+    }
+
     fn set_range_scaling(&mut self, new_scaling: u8) -> Result<(), E> {
         const DEFAULT_CROSSTALK_VALID_HEIGHT: u8 = 20; // default value of SYSRANGE__CROSSTALK_VALID_HEIGHT
 
@@ -156,7 +286,7 @@ where
         // apply scaling on part-to-part offset
         self.write_named_register(
             SYSRANGE__PART_TO_PART_RANGE_OFFSET,
-            self.config.ptp_offset / scaling,
+            (self.config.ptp_offset / scaling as i8) as u8,
         )?;
 
         // apply scaling on CrossTalkValidHeight
@@ -165,13 +295,6 @@ where
             DEFAULT_CROSSTALK_VALID_HEIGHT / scaling,
         )?;
 
-        // This function does not apply scaling to RANGE_IGNORE_VALID_HEIGHT.
-
-        // enable early convergence estimate only at 1x scaling
-        let rce = self.read_named_register(SYSRANGE__RANGE_CHECK_ENABLES)?;
-        let is_scaling_one: u8 = if scaling == 1 { 1 } else { 0 };
-        self.write_named_register(SYSRANGE__RANGE_CHECK_ENABLES, (rce & 0xFE) | is_scaling_one)?;
-
         Ok(())
     }
 }
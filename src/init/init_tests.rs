@@ -0,0 +1,359 @@
+extern crate std;
+
+use super::*;
+use crate::{
+    sim::{SimBus, SimI2c},
+    AmbientInterruptMode, DynamicMode, OperatingMode, RangeChecks, RangeInterruptMode,
+    RangeStatusErrorCode,
+};
+use embedded_hal::i2c::{ErrorKind, ErrorType, Operation};
+use std::{cell::RefCell, rc::Rc, vec, vec::Vec};
+
+/// Passes the transactions on to the simulated bus, recording the register and the
+/// first byte of each register write.
+struct RecordingI2c {
+    i2c: SimI2c,
+    writes: Rc<RefCell<Vec<(u16, u8)>>>,
+}
+
+impl ErrorType for RecordingI2c {
+    type Error = ErrorKind;
+}
+
+impl I2c for RecordingI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        // Register writes are the register address then the data, reads have a read instead
+        if let [Operation::Write([hi, lo]), Operation::Write([value, ..])] = &operations[..] {
+            let reg = u16::from_be_bytes([*hi, *lo]);
+            self.writes.borrow_mut().push((reg, *value));
+        }
+        self.i2c.transaction(address, operations)
+    }
+}
+
+#[test]
+fn apply_config_writes_changed_registers() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    // Not part of the change, so not written again.
+    sensor.set_register(SYSRANGE__VHV_REPEAT_RATE as u16, 7);
+
+    let mut config = Config::new();
+    config.set_range_max_convergence_time(20).unwrap();
+    config.set_ambient_analogue_gain_level(3).unwrap();
+    config.set_ambient_high_interrupt_threshold(1000);
+    tof.apply_config(&config).unwrap();
+
+    assert_eq!(sensor.register(SYSRANGE__MAX_CONVERGENCE_TIME as u16), 20);
+    assert_eq!(
+        sensor.register(SYSALS__ANALOGUE_GAIN as u16),
+        AMBIENT_ANALOGUE_GAIN_CODE[3]
+    );
+    assert_eq!(sensor.register_16bit(SYSALS__THRESH_HIGH as u16), 1000);
+    assert_eq!(sensor.register(SYSTEM__GROUPED_PARAMETER_HOLD as u16), 0);
+    assert_eq!(sensor.register(SYSRANGE__VHV_REPEAT_RATE as u16), 7);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(100));
+}
+
+#[test]
+fn apply_config_rescales_range() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut config = Config::new();
+    config.set_range_result_scaler(3).unwrap();
+    tof.apply_config(&config).unwrap();
+    assert_eq!(
+        sensor.register_16bit(RANGE_SCALER as u16),
+        RANGE_SCALAR_CODE[3]
+    );
+    assert_eq!(sensor.register(SYSRANGE__CROSSTALK_VALID_HEIGHT as u16), 6);
+}
+
+#[test]
+fn apply_config_rescales_range_thresholds_mm() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_interrupt_mode(RangeInterruptMode::LevelLow);
+    config.set_range_low_interrupt_threshold_mm(120).unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(sensor.register(SYSRANGE__THRESH_LOW as u16), 120);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(100));
+
+    config.set_range_result_scaler(3).unwrap();
+    tof.apply_config(&config).unwrap();
+    assert_eq!(sensor.register(SYSRANGE__THRESH_LOW as u16), 40);
+    sensor.set_range_mm(130);
+    tof.start_range_single().unwrap();
+    assert_eq!(tof.read_range_mm(), Err(Error::ResultNotReady));
+    sensor.set_range_mm(90);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(90));
+}
+
+#[test]
+fn ambient_threshold_lux() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_ambient_interrupt_mode(AmbientInterruptMode::LevelLow);
+    config
+        .set_ambient_low_interrupt_threshold_lux(50.0)
+        .unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(sensor.register_16bit(SYSALS__THRESH_LOW as u16), 158);
+
+    sensor.set_ambient_counts(160);
+    tof.start_ambient_single().unwrap();
+    assert_eq!(tof.read_ambient(), Err(Error::ResultNotReady));
+    sensor.set_ambient_counts(150);
+    let lux = tof.poll_ambient_lux_single_blocking().unwrap();
+    assert!(lux < 50.0);
+}
+
+#[test]
+fn range_ignore_rejects_cover_glass() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_result_scaler(2).unwrap();
+    config.set_range_ignore(2.0, 40).unwrap();
+    let mut checks = RangeChecks::new();
+    checks.set_range_ignore(true);
+    config.set_range_checks(checks);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(
+        sensor.register(SYSRANGE__RANGE_IGNORE_VALID_HEIGHT as u16),
+        20
+    );
+    assert_eq!(
+        sensor.register_16bit(SYSRANGE__RANGE_IGNORE_THRESHOLD as u16),
+        256
+    );
+    assert_eq!(sensor.register(SYSRANGE__RANGE_CHECK_ENABLES as u16), 0x12);
+
+    sensor.set_range_mm(10);
+    sensor.set_signal_rate(128);
+    assert_eq!(
+        tof.poll_range_mm_single_blocking(),
+        Err(Error::RangeStatusError(RangeStatusErrorCode::RangeIgnore))
+    );
+    sensor.set_signal_rate(512);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(10));
+    sensor.set_range_mm(60);
+    sensor.set_signal_rate(128);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(60));
+
+    config.set_range_result_scaler(1).unwrap();
+    tof.apply_config(&config).unwrap();
+    assert_eq!(
+        sensor.register(SYSRANGE__RANGE_IGNORE_VALID_HEIGHT as u16),
+        40
+    );
+    assert_eq!(sensor.register(SYSRANGE__RANGE_CHECK_ENABLES as u16), 0x13);
+    checks.set_range_ignore(false);
+    config.set_range_checks(checks);
+    tof.apply_config(&config).unwrap();
+    assert_eq!(sensor.register(SYSRANGE__RANGE_CHECK_ENABLES as u16), 0x11);
+    sensor.set_range_mm(10);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(10));
+}
+
+#[test]
+fn range_checks() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut checks = RangeChecks::new();
+    checks.set_early_convergence_estimate_threshold(1200);
+    checks.set_signal_to_noise_ratio(false);
+    checks.set_max_ambient_level_multiplier(2.0).unwrap();
+    let mut config = Config::new();
+    config.set_range_checks(checks);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(sensor.register(SYSRANGE__RANGE_CHECK_ENABLES as u16), 0x01);
+    assert_eq!(
+        sensor.register_16bit(SYSRANGE__EARLY_CONVERGENCE_ESTIMATE as u16),
+        1200
+    );
+    assert_eq!(
+        sensor.register(SYSRANGE__MAX_AMBIENT_LEVEL_MULT as u16),
+        0x20
+    );
+
+    // 2 Mcps gives 1000 counts in 0.5 ms
+    sensor.set_signal_rate(2 << 7);
+    assert_eq!(
+        tof.poll_range_mm_single_blocking(),
+        Err(Error::RangeStatusError(
+            RangeStatusErrorCode::EarlyConvergenceEstimate
+        ))
+    );
+    checks.set_early_convergence_estimate(false);
+    config.set_range_checks(checks);
+    tof.apply_config(&config).unwrap();
+    assert_eq!(sensor.register(SYSRANGE__RANGE_CHECK_ENABLES as u16), 0x00);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(100));
+}
+
+#[test]
+fn apply_config_moves_address() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut config = Config::new();
+    config.set_i2c_address(0x30);
+    tof.apply_config(&config).unwrap();
+    assert_eq!(sensor.address(), 0x30);
+    assert_eq!(tof.read_model_id(), Ok(0xB4));
+}
+
+#[test]
+fn apply_config_rejects_invalid_config() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut config = Config::new();
+    config.set_range_max_convergence_time(20).unwrap();
+    config.set_i2c_address(0x78);
+    assert_eq!(tof.apply_config(&config), Err(Error::InvalidAddress(0x78)));
+
+    let mut config = Config::new();
+    config.set_ambient_inter_measurement_period(200).unwrap();
+    config.set_ambient_integration_period(200).unwrap();
+    assert_eq!(
+        tof.apply_config(&config),
+        Err(Error::InvalidConfigurationValue(200))
+    );
+
+    let mut config = Config::new();
+    config.set_range_max_convergence_time(10).unwrap();
+    config.set_range_inter_measurement_period(20).unwrap();
+    config.set_range_max_convergence_time(30).unwrap();
+    assert_eq!(
+        tof.apply_config(&config),
+        Err(Error::InvalidConfigurationValue(20))
+    );
+    assert_eq!(sensor.register(SYSRANGE__MAX_CONVERGENCE_TIME as u16), 49);
+}
+#[test]
+fn apply_config_holds_grouped_registers() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let writes = Rc::new(RefCell::new(Vec::new()));
+    let i2c = RecordingI2c {
+        i2c: bus.i2c(),
+        writes: writes.clone(),
+    };
+    let mut tof = VL6180X::new(i2c).unwrap();
+    writes.borrow_mut().clear();
+
+    let mut config = Config::new();
+    config.set_range_max_convergence_time(20).unwrap();
+    config.set_ambient_analogue_gain_level(3).unwrap();
+    config.set_ambient_integration_period(50).unwrap();
+    config.set_ambient_high_interrupt_threshold(1000);
+    tof.apply_config(&config).unwrap();
+
+    let hold = SYSTEM__GROUPED_PARAMETER_HOLD as u16;
+    assert_eq!(
+        *writes.borrow(),
+        vec![
+            (hold, 1),
+            (SYSALS__ANALOGUE_GAIN as u16, AMBIENT_ANALOGUE_GAIN_CODE[3]),
+            (SYSALS__INTEGRATION_PERIOD as u16, 0),
+            (SYSALS__THRESH_HIGH as u16, 0x03),
+            (hold, 0),
+            (SYSRANGE__MAX_CONVERGENCE_TIME as u16, 20),
+        ]
+    );
+    assert_eq!(sensor.register_16bit(SYSALS__INTEGRATION_PERIOD as u16), 49);
+}
+
+#[test]
+fn apply_interrupt_thresholds_in_continuous_mode() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut tof = tof.start_range_continuous_mode().unwrap();
+    let mut config = Config::new();
+    config.set_range_low_interrupt_threshold(20);
+    config.set_range_high_interrupt_threshold(200);
+    config.set_range_max_convergence_time(20).unwrap();
+    tof.apply_interrupt_thresholds(&config).unwrap();
+
+    assert_eq!(sensor.register(SYSRANGE__THRESH_LOW as u16), 20);
+    assert_eq!(sensor.register(SYSRANGE__THRESH_HIGH as u16), 200);
+    assert_eq!(sensor.register(SYSRANGE__MAX_CONVERGENCE_TIME as u16), 49);
+}
+
+#[test]
+fn set_range_thresholds_mm_in_continuous_mode() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_result_scaler(2).unwrap();
+    let tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    let mut tof = tof.start_range_continuous_mode().unwrap();
+    tof.set_range_thresholds_mm(41, 300).unwrap();
+
+    assert_eq!(sensor.register(SYSRANGE__THRESH_LOW as u16), 21);
+    assert_eq!(sensor.register(SYSRANGE__THRESH_HIGH as u16), 150);
+    assert_eq!(sensor.register(SYSTEM__GROUPED_PARAMETER_HOLD as u16), 0);
+    assert_eq!(
+        tof.set_range_thresholds_mm(20, 511),
+        Err(Error::InvalidConfigurationValue(511))
+    );
+    assert_eq!(
+        tof.set_range_thresholds_mm(100, 50),
+        Err(Error::InvalidConfigurationValue(100))
+    );
+    assert_eq!(sensor.register(SYSRANGE__THRESH_HIGH as u16), 150);
+}
+
+#[test]
+fn set_ambient_thresholds_in_continuous_mode() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut tof = tof.start_ambient_continuous_mode().unwrap();
+    tof.set_ambient_thresholds(100, 2000).unwrap();
+
+    assert_eq!(sensor.register_16bit(SYSALS__THRESH_LOW as u16), 100);
+    assert_eq!(sensor.register_16bit(SYSALS__THRESH_HIGH as u16), 2000);
+    assert_eq!(sensor.register(SYSTEM__GROUPED_PARAMETER_HOLD as u16), 0);
+    assert_eq!(
+        tof.set_ambient_thresholds(2000, 100),
+        Err(Error::InvalidConfigurationValue(2000))
+    );
+}
+
+#[test]
+fn try_apply_config_only_when_ready() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof: VL6180X<DynamicMode, _, _> = VL6180X::new(bus.i2c()).unwrap().into_dynamic_mode();
+    tof.try_start_range_continuous_mode().unwrap();
+    assert_eq!(
+        tof.try_apply_config(&Config::new()),
+        Err(Error::InvalidMethod(OperatingMode::RangeContinuous))
+    );
+    assert_eq!(tof.try_apply_interrupt_thresholds(&Config::new()), Ok(()));
+    assert_eq!(tof.try_set_range_thresholds_mm(10, 20), Ok(()));
+    tof.try_stop_range_continuous_mode().unwrap();
+    tof.try_power_off(&mut sensor.x_shutdown_pin()).unwrap();
+    assert_eq!(
+        tof.try_set_ambient_thresholds(10, 20),
+        Err(Error::InvalidMethod(OperatingMode::PoweredOff))
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_apply_config() {
+    let (bus, sensor) = SimBus::with_sensor();
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        let mut config = Config::new();
+        config.set_range_max_convergence_time(20).unwrap();
+        config.set_range_high_interrupt_threshold(150);
+        tof.apply_config(&config).await.unwrap();
+        tof.set_ambient_thresholds(5, 500).await.unwrap();
+    });
+    assert_eq!(sensor.register_16bit(SYSALS__THRESH_HIGH as u16), 500);
+    assert_eq!(sensor.register(SYSRANGE__MAX_CONVERGENCE_TIME as u16), 20);
+    assert_eq!(sensor.register(SYSRANGE__THRESH_HIGH as u16), 150);
+}
@@ -0,0 +1,125 @@
+use crate::{
+    error::{Error, Error2},
+    mode::{AllowReadMeasurement, ReadyMode},
+    read_measurements::{
+        ambient_read_timeout_us, convert_raw_ambient_to_lux, range_read_timeout_us,
+    },
+    register::ResultInterruptStatusGpioCode,
+    AmbientInterruptMode, GpioPolarity, RangeInterruptMode, VL6180XwPins, WaitStrategy,
+};
+use embedded_hal::{
+    digital::{InputPin, OutputPin},
+    i2c::I2c,
+};
+
+impl<MODE, I2C, E, OP, IP, PE, W> VL6180XwPins<MODE, I2C, OP, IP, W>
+where
+    I2C: I2c<Error = E>,
+    OP: OutputPin,
+    IP: InputPin<Error = PE>,
+    MODE: AllowReadMeasurement,
+    W: WaitStrategy,
+{
+    /// Blocking read of the range measurement, waiting on the `interrupt_pin`
+    /// instead of polling the sensor over I2C.
+    /// The reading (whether single or continuous) must already have been started and the
+    /// [range_interrupt_mode](crate::config::Config::set_range_interrupt_mode) must not be disabled.
+    /// The `interrupt_pin` is the GPIO configured as interrupt output, GPIO1 by default, see
+    /// [set_gpio1_function](crate::config::Config::set_gpio1_function).
+    ///
+    /// Returns [Error::Timeout] if the `interrupt_pin` is not asserted in time.
+    ///
+    /// Note: in interleaved mode the interrupt is shared with the ambient light measurement.
+    pub fn read_range_mm_interrupt_blocking(&mut self) -> Result<u16, Error2<E, PE>> {
+        if let RangeInterruptMode::Disabled = self.vl6180x.config.range_interrupt_mode {
+            return Err(Error2::DriverError(Error::InvalidConfigurationValue(
+                RangeInterruptMode::Disabled as u16,
+            )));
+        }
+        let timeout_us = range_read_timeout_us(&self.vl6180x.config, MODE::INTERLEAVED);
+        self.wait_interrupt(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)?;
+        Ok(self.vl6180x.get_range_val_and_status()?)
+    }
+
+    /// Blocking read of the ambient light measurement, waiting on the `interrupt_pin`
+    /// instead of polling the sensor over I2C.
+    /// The reading (whether single or continuous) must already have been started and the
+    /// [ambient_interrupt_mode](crate::config::Config::set_ambient_interrupt_mode) must not be disabled.
+    /// The `interrupt_pin` is the GPIO configured as interrupt output, GPIO1 by default, see
+    /// [set_gpio1_function](crate::config::Config::set_gpio1_function).
+    ///
+    /// Returns [Error::Timeout] if the `interrupt_pin` is not asserted in time.
+    ///
+    /// Note: in interleaved mode the interrupt is shared with the range measurement.
+    pub fn read_ambient_lux_interrupt_blocking(&mut self) -> Result<f32, Error2<E, PE>> {
+        if let AmbientInterruptMode::Disabled = self.vl6180x.config.ambient_interrupt_mode {
+            return Err(Error2::DriverError(Error::InvalidConfigurationValue(
+                AmbientInterruptMode::Disabled as u16,
+            )));
+        }
+        let timeout_us = ambient_read_timeout_us(&self.vl6180x.config, MODE::INTERLEAVED);
+        self.wait_interrupt(ResultInterruptStatusGpioCode::NoAmbientEvents, timeout_us)?;
+        let raw_ambient = self.vl6180x.get_ambient_val_and_status()?;
+        Ok(convert_raw_ambient_to_lux(
+            &self.vl6180x.config,
+            raw_ambient,
+        ))
+    }
+
+    /// Waits until `look_for` is no longer reported in the interrupt status, first on the
+    /// interrupt line, then polling the status in case another event asserted the line.
+    /// Returns [Error::Timeout] if the event is not reported within `timeout_us`.
+    fn wait_interrupt(
+        &mut self,
+        look_for: ResultInterruptStatusGpioCode,
+        timeout_us: u32,
+    ) -> Result<(), Error2<E, PE>> {
+        let waited_us = self.wait_interrupt_pin(timeout_us)?;
+        // The line is shared by the range and ambient light events
+        self.vl6180x
+            .wait_for_event(look_for, timeout_us.saturating_sub(waited_us))?;
+        Ok(())
+    }
+
+    /// Waits with the [WaitStrategy] until the interrupt line is asserted, with the polarity
+    /// of the GPIO configured as interrupt output, and returns the time waited.
+    /// Returns [Error::Timeout] if the line is not asserted within `timeout_us`.
+    fn wait_interrupt_pin(&mut self, timeout_us: u32) -> Result<u32, Error2<E, PE>> {
+        let active_high = match self.vl6180x.config.interrupt_polarity() {
+            Some(polarity) => polarity == GpioPolarity::ActiveHigh,
+            None => return Err(Error2::DriverError(Error::NoInterruptOutput)),
+        };
+        let mut waited_us: u32 = 0;
+        while self.interrupt_pin.is_high().map_err(Error2::GpioPinError)? != active_high {
+            if waited_us >= timeout_us {
+                return Err(Error2::DriverError(Error::Timeout));
+            }
+            waited_us = waited_us.saturating_add(self.vl6180x.wait.wait(timeout_us - waited_us));
+        }
+        Ok(waited_us)
+    }
+}
+
+impl<I2C, E, OP, IP, PE, W> VL6180XwPins<ReadyMode, I2C, OP, IP, W>
+where
+    I2C: I2c<Error = E>,
+    OP: OutputPin,
+    IP: InputPin<Error = PE>,
+    W: WaitStrategy,
+{
+    /// Starts a single range measurement then calls
+    /// [`read_range_mm_interrupt_blocking`](VL6180XwPins::read_range_mm_interrupt_blocking)
+    /// to wait for the result.
+    pub fn poll_range_mm_single_interrupt_blocking(&mut self) -> Result<u16, Error2<E, PE>> {
+        self.vl6180x.start_range_single_direct()?;
+        self.read_range_mm_interrupt_blocking()
+    }
+
+    /// Starts a single ambient light measurement then calls
+    /// [`read_ambient_lux_interrupt_blocking`](VL6180XwPins::read_ambient_lux_interrupt_blocking)
+    /// to wait for the result.
+    pub fn poll_ambient_lux_single_interrupt_blocking(&mut self) -> Result<f32, Error2<E, PE>> {
+        self.vl6180x.start_ambient_single_direct()?;
+        self.read_ambient_lux_interrupt_blocking()
+    }
+}
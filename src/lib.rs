@@ -3,7 +3,7 @@
 //!
 //! for more examples please see [vl6180x_stm32f401_examples](https://github.com/shaoyuancc/vl6180x_stm32f401_examples)
 //!
-//! ```rust
+//! ```rust,ignore
 //! #![no_std]
 //! #![no_main]
 //!
@@ -17,12 +17,13 @@
 //!
 //! #[entry]
 //! fn main() -> ! {
-//!     if let (Some(dp), Some(_cp)) = (
+//!     if let (Some(dp), Some(cp)) = (
 //!         pac::Peripherals::take(),
 //!         cortex_m::peripheral::Peripherals::take(),
 //!     ) {
 //!         let rcc = dp.RCC.constrain();
 //!         let clocks = rcc.cfgr.sysclk(48.MHz()).freeze();
+//!         let delay = cp.SYST.delay(&clocks);
 //!
 //!         let gpiob = dp.GPIOB.split();
 //!         let scl = gpiob
@@ -37,8 +38,8 @@
 //!             .set_open_drain();
 //!         let i2c = dp.I2C1.i2c((scl, sda), 400.kHz(), &clocks);
 //!
-//!         //! To create sensor with default configuration:
-//!         let mut tof = vl6180x::VL6180X::new(i2c).expect("vl");
+//!         //! To create sensor with default configuration, sleeping between polls:
+//!         let mut tof = vl6180x::VL6180X::new_with_delay(i2c, delay).expect("vl");
 //!
 //!         loop {
 //!             match tof.poll_range_mm_single_blocking() {
@@ -72,35 +73,57 @@
     warnings
 )]
 #![allow(dead_code)]
-pub use crate::register::ResultInterruptStatusGpioCode;
+pub use crate::register::{
+    AmbientStatusErrorCode, RangeStatusErrorCode, ResultInterruptStatusGpioCode,
+};
 pub use config::*;
-use embedded_hal::blocking::i2c::{Write, WriteRead};
-use embedded_hal::digital::v2::{InputPin, OutputPin};
-pub use error::Error;
+pub use detached::DetachedVL6180X;
+pub use device_status::DeviceInfo;
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::i2c::I2c;
+pub use error::{Error, Error2, SensorArrayError};
 pub use mode::*;
+pub use read_measurements::{AmbientMeasurement, RangeMeasurement, ScaledRangeMeasurement};
+pub use sensor_array::SensorArray;
+pub use wait::{BusyWait, DelayWait, InterruptWait, WaitStrategy};
+#[cfg(feature = "async")]
+pub mod asynch;
+mod calibration;
+#[cfg(feature = "embedded-hal-02")]
+pub mod compat;
 mod config;
+mod detached;
 mod device_status;
 mod error;
 mod i2c_interface;
 mod init;
+mod interrupt_pin;
 mod mode;
 mod read_measurements;
 mod register;
+mod sensor_array;
+#[cfg(any(test, feature = "sim"))]
+pub mod sim;
 mod start_stop_measurements;
+mod wait;
 
 /// VL6180 interface
+///
+/// `W` is the [WaitStrategy] of the blocking reads, see
+/// [with_wait_strategy](VL6180X::with_wait_strategy).
 #[derive(Debug, Clone, Copy)]
-pub struct VL6180X<MODE, I2C: Write + WriteRead> {
+pub struct VL6180X<MODE, I2C: I2c, W = BusyWait> {
     mode: MODE,
     com: I2C,
     config: Config,
+    wait: W,
 }
 
 /// Convenience container for VL6180, x_shutdown_pin and interrupt_pin
 #[derive(Debug, Clone, Copy)]
-pub struct VL6180XwPins<MODE, I2C: Write + WriteRead, OP: OutputPin, IP: InputPin> {
+pub struct VL6180XwPins<MODE, I2C: I2c, OP: OutputPin, IP: InputPin, W = BusyWait> {
     /// VL6180
-    pub vl6180x: VL6180X<MODE, I2C>,
+    pub vl6180x: VL6180X<MODE, I2C, W>,
     /// X Shutdown Pin, output high => powered on, output low => powered off.
     /// Should call [VL6180X::power_off] and [VL6180X::power_on_and_init]
     /// (Or the equivalent DynamicMode try methods) instead of
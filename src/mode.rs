@@ -5,50 +5,74 @@ mod ready;
 
 pub use continuous::*;
 pub use dynamic::*;
-use embedded_hal::blocking::i2c::{Write, WriteRead};
-use embedded_hal::digital::v2::OutputPin;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::I2c;
 pub use powered_off::*;
 pub use ready::*;
 
 use crate::error::Error;
-use crate::VL6180X;
+use crate::register::{AMBIENT_HISTORY_LEN, RANGE_HISTORY_LEN};
+use crate::{Config, DeviceInfo, RangeMeasurement, WaitStrategy, VL6180X};
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
-    fn into_mode<MODE2>(self, mode: MODE2) -> VL6180X<MODE2, I2C> {
+    fn into_mode<MODE2>(self, mode: MODE2) -> VL6180X<MODE2, I2C, W> {
         VL6180X {
             mode,
             com: self.com,
             config: self.config,
+            wait: self.wait,
         }
     }
+
+    /// Release the I2C bus, e.g. to hand it to another driver after
+    /// [power_off](VL6180X::power_off).
+    /// The driver can be rebuilt with [new_powered_off](VL6180X::new_powered_off).
+    pub fn release(self) -> I2C {
+        self.com
+    }
+
+    /// Release the I2C bus and the [WaitStrategy].
+    pub fn release_with_wait_strategy(self) -> (I2C, W) {
+        (self.com, self.wait)
+    }
 }
 /// Allow communication with the device (the device is not powered off)
 pub trait AllowCommunication {}
 
 /// Operating modes with this trait have an implementation for reading measurements
-pub trait AllowReadMeasurement {}
+pub trait AllowReadMeasurement {
+    /// Whether range and ambient light measurements run one after the other, so waiting
+    /// for a result can take the time of both.
+    const INTERLEAVED: bool = false;
+}
 
 /// Operating modes with this trait have an implementation for starting a single
 /// ambient light measurement
-
 pub trait AllowStartAmbientSingle {}
 
 /// Operating modes with this trait have an implementation for starting a single
 /// range measurement
 pub trait AllowStartRangeSingle {}
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
     MODE: AllowReadMeasurement,
+    W: WaitStrategy,
 {
     /// Blocking read of the range mesurement.
     /// The reading (whether single or continuous) must already have been started.
+    ///
+    /// Waits with the [WaitStrategy] between polls. Returns [Error::Timeout] if no result
+    /// arrives within the range inter-measurement period plus the measurement time derived
+    /// from the max convergence time and readout averaging period of the [Config]. In
+    /// interleaved mode the ambient light measurement time is added.
     pub fn read_range_mm_blocking(&mut self) -> Result<u16, Error<E>> {
-        self.read_range_mm_blocking_direct()
+        self.read_range_mm_blocking_direct(MODE::INTERLEAVED)
     }
 
     /// Non-blocking read of the range measurement.
@@ -58,10 +82,51 @@ where
         self.read_range_mm_direct()
     }
 
+    /// Blocking read of the range measurement together with its signal diagnostics.
+    /// The reading (whether single or continuous) must already have been started.
+    ///
+    /// Unlike [`read_range_mm_blocking`](VL6180X::read_range_mm_blocking), a measurement
+    /// with an error status is returned rather than turned into an error, see
+    /// [RangeMeasurement::status].
+    pub fn read_range_detailed_blocking(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        self.read_range_detailed_blocking_direct(MODE::INTERLEAVED)
+    }
+
+    /// Non-blocking read of the range measurement together with its signal diagnostics.
+    /// The reading (whether single or continuous) must already have been started.
+    /// Returns [Error::ResultNotReady] if the result is not ready.
+    pub fn read_range_detailed(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        self.read_range_detailed_direct()
+    }
+
+    /// Read the range history buffer in a single transfer, most recent measurement first.
+    ///
+    /// The [history_buffer_mode](crate::config::Config::set_history_buffer_mode) must be
+    /// `Range`, otherwise returns [Error::InvalidConfigurationValue]. The buffer only holds
+    /// range values, the status of each measurement is not kept. Slots that have not been
+    /// filled since the buffer was last cleared read as 0.
+    pub fn read_range_history_mm(&mut self) -> Result<[u16; RANGE_HISTORY_LEN], Error<E>> {
+        self.read_range_history_mm_direct()
+    }
+
+    /// Read the raw ambient light history buffer in a single transfer, most recent measurement first.
+    ///
+    /// The [history_buffer_mode](crate::config::Config::set_history_buffer_mode) must be
+    /// `Ambient`, otherwise returns [Error::InvalidConfigurationValue]. Slots that have not been
+    /// filled since the buffer was last cleared read as 0.
+    pub fn read_ambient_history(&mut self) -> Result<[u16; AMBIENT_HISTORY_LEN], Error<E>> {
+        self.read_ambient_history_direct()
+    }
+
     /// Blocking read of the ambient light mesurement.
     /// The reading (whether single or continuous) must already have been started.
+    ///
+    /// Waits with the [WaitStrategy] between polls. Returns [Error::Timeout] if no result
+    /// arrives within the ambient inter-measurement period plus the measurement time derived
+    /// from the integration period and readout averaging period of the [Config]. In
+    /// interleaved mode the range measurement time is added.
     pub fn read_ambient_lux_blocking(&mut self) -> Result<f32, Error<E>> {
-        self.read_ambient_lux_blocking_direct()
+        self.read_ambient_lux_blocking_direct(MODE::INTERLEAVED)
     }
 
     /// Non-blocking read of the ambient light measurement.
@@ -74,7 +139,7 @@ where
     /// Blocking read of the raw ambient light mesurement.
     /// The reading (whether single or continuous) must already have been started.
     pub fn read_ambient_blocking(&mut self) -> Result<u16, Error<E>> {
-        self.read_ambient_blocking_direct()
+        self.read_ambient_blocking_direct(MODE::INTERLEAVED)
     }
 
     /// Non-blocking read of the raw ambient light measurement.
@@ -85,56 +150,65 @@ where
     }
 }
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
     MODE: AllowStartAmbientSingle,
+    W: WaitStrategy,
 {
     /// Trigger ambient light measurement in a non-blocking way.
     ///
     /// Does not return the result. To get the measured value the host has the following options:
     /// 1. Check regularly to see if the result is ready with [`read_ambient_lux`](VL6180X::read_ambient_lux)
-    /// or [`read_ambient`](VL6180X::read_ambient)
+    ///    or [`read_ambient`](VL6180X::read_ambient)
     /// 2. Call [`read_ambient_lux_blocking`](VL6180X::read_ambient_lux_blocking) or
-    /// [`read_ambient_blocking`](VL6180X::read_ambient_blocking) to have the driver
-    /// perform the regular checks in a blocking way.
+    ///    [`read_ambient_blocking`](VL6180X::read_ambient_blocking) to have the driver
+    ///    perform the regular checks in a blocking way.
     /// 3. Wait for the ambient interrupt to be triggered, indicating that the
-    /// new sample is ready, then call the methods listed in option 1.
+    ///    new sample is ready, then call the methods listed in option 1.
     pub fn start_ambient_single(&mut self) -> Result<(), Error<E>> {
         self.start_ambient_single_direct()?;
         Ok(())
     }
 }
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
     MODE: AllowStartRangeSingle,
+    W: WaitStrategy,
 {
     /// Trigger range mesurement in a non-blocking way.
     ///
     /// Does not return the result. To get the measured value the host has the following options:
     /// 1. Check regularly to see if the result is ready with [`read_range_mm()`](VL6180X::read_range_mm)
     /// 2. Call [`read_range_mm_blocking()`](VL6180X::read_range_mm_blocking) to have the driver
-    /// perform the regular checks in a blocking way.
+    ///    perform the regular checks in a blocking way.
     /// 3. Wait for the range interrupt to be triggered, indicating that the
-    /// new sample is ready, then call [`read_range_mm()`](VL6180X::read_range_mm).
+    ///    new sample is ready, then call [`read_range_mm()`](VL6180X::read_range_mm).
     pub fn start_range_single(&mut self) -> Result<(), Error<E>> {
         self.start_range_single_direct()?;
         Ok(())
     }
 }
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
     MODE: AllowCommunication,
+    W: WaitStrategy,
 {
     /// Read the model id of the sensor. Should return 0xB4.
     pub fn read_model_id(&mut self) -> Result<u8, Error<E>> {
         self.read_model_id_direct()
     }
 
+    /// Read the model id, the silicon and module revisions and the manufacturing date
+    /// and time of the sensor.
+    pub fn read_device_info(&mut self) -> Result<DeviceInfo, Error<E>> {
+        self.read_device_info_direct()
+    }
+
     /// Read the current interrupt status of the sensor.
     /// Can be in multiple states of [ResultInterruptStatusGpioCode](crate::register::ResultInterruptStatusGpioCode) at once.
     /// Use [ResultInterruptStatusGpioCode::has_status](crate::register::ResultInterruptStatusGpioCode::has_status) to look for particular states.
@@ -157,6 +231,12 @@ where
         self.clear_range_interrupt_direct()
     }
 
+    /// Clear the history buffer, see
+    /// [history_buffer_mode](crate::config::Config::set_history_buffer_mode).
+    pub fn clear_history_buffer(&mut self) -> Result<(), Error<E>> {
+        self.clear_history_buffer_direct()
+    }
+
     /// Clear all interrupts (error, ambient and range)
     pub fn clear_all_interrupts(&mut self) -> Result<(), Error<E>> {
         self.clear_all_interrupts_direct()
@@ -166,7 +246,7 @@ where
     pub fn power_off<PE, P: OutputPin<Error = PE>>(
         self,
         x_shutdown_pin: &mut P,
-    ) -> Result<VL6180X<PoweredOffMode, I2C>, Error<PE>> {
+    ) -> Result<VL6180X<PoweredOffMode, I2C, W>, Error<PE>> {
         self.power_off_direct(x_shutdown_pin)?;
         Ok(self.into_mode(PoweredOffMode {}))
     }
@@ -178,8 +258,40 @@ where
     /// Only allows values between 0x08 and 0x77 as the device uses a 7 bit address and
     /// 0x00 - 0x07 and 0x78 - 0x7F are reserved
     ///
-    /// AN4478: Using multiple VL6180X's in a single design
+    /// AN4478: Using multiple VL6180X's in a single design,
+    /// see [SensorArray](crate::SensorArray) which implements the sequence.
     pub fn change_i2c_address(&mut self, new_address: u8) -> Result<(), Error<E>> {
         self.change_i2c_address_direct(new_address)
     }
+
+    /// Update the range and ambient interrupt thresholds to those of `config`, also
+    /// while a continuous mode is running. The other values of `config` are ignored.
+    ///
+    /// The changed thresholds are written within a grouped parameter hold, so the sensor
+    /// never compares a measurement against half-updated thresholds.
+    pub fn apply_interrupt_thresholds(&mut self, config: &Config) -> Result<(), Error<E>> {
+        Ok(self.apply_interrupt_thresholds_direct(config)?)
+    }
+
+    /// Set the range interrupt thresholds (mm), e.g. while
+    /// [RangeContinuousMode] is running.
+    ///
+    /// Both thresholds are written within a grouped parameter hold, so the sensor picks
+    /// them up together and no measurement is compared against a half-updated window.
+    /// The thresholds are rounded to the [range scaling](crate::Config::set_range_result_scaler)
+    /// and must be at most 255 times the scaling, and `low_mm` must not exceed `high_mm`,
+    /// otherwise [Error::InvalidConfigurationValue] is returned.
+    pub fn set_range_thresholds_mm(&mut self, low_mm: u16, high_mm: u16) -> Result<(), Error<E>> {
+        self.set_range_thresholds_mm_direct(low_mm, high_mm)
+    }
+
+    /// Set the ambient light interrupt thresholds, in raw counts, e.g. while
+    /// [AmbientContinuousMode] is running.
+    ///
+    /// Both thresholds are written within a grouped parameter hold, see
+    /// [set_range_thresholds_mm](VL6180X::set_range_thresholds_mm).
+    /// Returns [Error::InvalidConfigurationValue] if `low` exceeds `high`.
+    pub fn set_ambient_thresholds(&mut self, low: u16, high: u16) -> Result<(), Error<E>> {
+        self.set_ambient_thresholds_direct(low, high)
+    }
 }
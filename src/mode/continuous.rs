@@ -1,5 +1,8 @@
-use crate::{error::Error, AllowCommunication, VL6180X};
-use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::{
+    error::Error, AllowCommunication, AmbientMeasurement, ScaledRangeMeasurement, WaitStrategy,
+    VL6180X,
+};
+use embedded_hal::i2c::I2c;
 
 use super::{AllowReadMeasurement, AllowStartAmbientSingle, AllowStartRangeSingle, ReadyMode};
 
@@ -13,15 +16,29 @@ impl AllowStartAmbientSingle for RangeContinuousMode {}
 
 impl AllowCommunication for RangeContinuousMode {}
 
-impl<I2C, E> VL6180X<RangeContinuousMode, I2C>
+impl<I2C, E, W> VL6180X<RangeContinuousMode, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     /// Stops range continuous mode.
-    pub fn stop_range_continuous_mode(mut self) -> Result<VL6180X<ReadyMode, I2C>, Error<E>> {
+    pub fn stop_range_continuous_mode(mut self) -> Result<VL6180X<ReadyMode, I2C, W>, Error<E>> {
         self.toggle_range_continuous_direct()?;
         Ok(self.into_mode(ReadyMode {}))
     }
+
+    /// Blocking read of the next range measurement, adjusting the range scaling to the
+    /// distance.
+    ///
+    /// When the measurement overflows, or the range fits a finer scaling, the scaling is
+    /// changed and a measurement taken with the new scaling read instead, dropping the one
+    /// in progress, see
+    /// [poll_range_mm_single_auto_scaling_blocking](VL6180X::poll_range_mm_single_auto_scaling_blocking).
+    pub fn read_range_mm_auto_scaling_blocking(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        self.read_range_mm_auto_scaling_blocking_direct()
+    }
 }
 
 /// Mode in which continuous ambient light measurements are being taken by the sensor
@@ -34,15 +51,27 @@ impl AllowStartRangeSingle for AmbientContinuousMode {}
 
 impl AllowCommunication for AmbientContinuousMode {}
 
-impl<I2C, E> VL6180X<AmbientContinuousMode, I2C>
+impl<I2C, E, W> VL6180X<AmbientContinuousMode, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     /// Stops ambient continuous mode.
-    pub fn stop_ambient_continuous_mode(mut self) -> Result<VL6180X<ReadyMode, I2C>, Error<E>> {
+    pub fn stop_ambient_continuous_mode(mut self) -> Result<VL6180X<ReadyMode, I2C, W>, Error<E>> {
         self.toggle_ambient_continuous_direct()?;
         Ok(self.into_mode(ReadyMode {}))
     }
+
+    /// Blocking read of the next ambient light measurement, adjusting the analogue gain
+    /// to the light level.
+    ///
+    /// When the measurement overflows, or the count is too low or too high for a good
+    /// reading, the gain is changed and a measurement taken with the new gain read instead,
+    /// dropping the one in progress, see
+    /// [poll_ambient_lux_single_auto_gain_blocking](VL6180X::poll_ambient_lux_single_auto_gain_blocking).
+    pub fn read_ambient_lux_auto_gain_blocking(&mut self) -> Result<AmbientMeasurement, Error<E>> {
+        self.read_ambient_lux_auto_gain_blocking_direct()
+    }
 }
 
 /// Mode in which continuous ambient and range measurements are being taken by the sensor.
@@ -52,16 +81,21 @@ where
 #[derive(Debug, Copy, Clone)]
 pub struct InterleavedContinuousMode {}
 
-impl AllowReadMeasurement for InterleavedContinuousMode {}
+impl AllowReadMeasurement for InterleavedContinuousMode {
+    const INTERLEAVED: bool = true;
+}
 
 impl AllowCommunication for InterleavedContinuousMode {}
 
-impl<I2C, E> VL6180X<InterleavedContinuousMode, I2C>
+impl<I2C, E, W> VL6180X<InterleavedContinuousMode, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     /// Stops interleaved continuous mode.
-    pub fn stop_interleaved_continuous_mode(mut self) -> Result<VL6180X<ReadyMode, I2C>, Error<E>> {
+    pub fn stop_interleaved_continuous_mode(
+        mut self,
+    ) -> Result<VL6180X<ReadyMode, I2C, W>, Error<E>> {
         self.stop_interleaved_continuous_direct()?;
         Ok(self.into_mode(ReadyMode {}))
     }
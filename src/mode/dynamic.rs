@@ -1,9 +1,10 @@
 use crate::error::{Error, Error2};
-use crate::VL6180X;
-use embedded_hal::{
-    blocking::i2c::{Write, WriteRead},
-    digital::v2::OutputPin,
+use crate::register::{AMBIENT_HISTORY_LEN, RANGE_HISTORY_LEN};
+use crate::{
+    AmbientMeasurement, Config, DeviceInfo, RangeMeasurement, ScaledRangeMeasurement, WaitStrategy,
+    VL6180X,
 };
+use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
 use OperatingMode::*;
 
 /// A mode where the state is kept track of at runtime, instead of being
@@ -37,11 +38,17 @@ impl DynamicMode {
             operating_mode: Ready,
         }
     }
+
+    /// Whether range and ambient light measurements run one after the other.
+    fn interleaved(&self) -> bool {
+        self.operating_mode == InterleavedContinuous
+    }
 }
 
-impl<I2C, E> VL6180X<DynamicMode, I2C>
+impl<I2C, E, W> VL6180X<DynamicMode, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     /// Same functionality as [`poll_range_mm_single_blocking()`](VL6180X::poll_range_mm_single_blocking)
     /// but with a check on the current [OperatingMode].
@@ -53,6 +60,30 @@ where
         self.poll_range_mm_single_blocking_direct()
     }
 
+    /// Same functionality as [`poll_range_mm_single_auto_scaling_blocking()`](VL6180X::poll_range_mm_single_auto_scaling_blocking)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
+    pub fn try_poll_range_mm_single_auto_scaling_blocking(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        if self.mode.operating_mode != Ready {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.poll_range_mm_single_auto_scaling_blocking_direct()
+    }
+
+    /// Same functionality as [`poll_range_detailed_single_blocking()`](VL6180X::poll_range_detailed_single_blocking)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
+    pub fn try_poll_range_detailed_single_blocking(
+        &mut self,
+    ) -> Result<RangeMeasurement, Error<E>> {
+        if self.mode.operating_mode != Ready {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.poll_range_detailed_single_blocking_direct()
+    }
+
     /// Same functionality as [`poll_ambient_lux_single_blocking()`](VL6180X::poll_ambient_lux_single_blocking)
     /// but with a check on the current [OperatingMode].
     /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
@@ -63,6 +94,70 @@ where
         self.poll_ambient_lux_single_blocking_direct()
     }
 
+    /// Same functionality as [`poll_ambient_lux_single_auto_gain_blocking()`](VL6180X::poll_ambient_lux_single_auto_gain_blocking)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
+    pub fn try_poll_ambient_lux_single_auto_gain_blocking(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        if self.mode.operating_mode != Ready {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.poll_ambient_lux_single_auto_gain_blocking_direct()
+    }
+
+    /// Same functionality as [`calibrate_range_offset()`](VL6180X::calibrate_range_offset)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
+    pub fn try_calibrate_range_offset(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<i8, Error<E>> {
+        if self.mode.operating_mode != Ready {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.calibrate_range_offset_direct(target_distance_mm, samples)
+    }
+
+    /// Same functionality as [`calibrate_crosstalk()`](VL6180X::calibrate_crosstalk)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
+    pub fn try_calibrate_crosstalk(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<u16, Error<E>> {
+        if self.mode.operating_mode != Ready {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.calibrate_crosstalk_direct(target_distance_mm, samples)
+    }
+
+    /// Same functionality as [`calibrate_ambient_lux()`](VL6180X::calibrate_ambient_lux)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
+    pub fn try_calibrate_ambient_lux(
+        &mut self,
+        reference_lux: f32,
+        samples: u8,
+    ) -> Result<f32, Error<E>> {
+        if self.mode.operating_mode != Ready {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.calibrate_ambient_lux_direct(reference_lux, samples)
+    }
+
+    /// Same functionality as [`apply_config()`](VL6180X::apply_config)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
+    pub fn try_apply_config(&mut self, config: &Config) -> Result<(), Error<E>> {
+        if self.mode.operating_mode != Ready {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.apply_config_direct(config)
+    }
+
     /// Same functionality as [`start_range_continuous_mode()`](VL6180X::start_range_continuous_mode)
     /// but with a check on the current [OperatingMode].
     /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
@@ -87,6 +182,18 @@ where
         Ok(())
     }
 
+    /// Same functionality as [`read_range_mm_auto_scaling_blocking()`](VL6180X::read_range_mm_auto_scaling_blocking)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [RangeContinuous], otherwise returns [Error::InvalidMethod]
+    pub fn try_read_range_mm_auto_scaling_blocking(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        if self.mode.operating_mode != RangeContinuous {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.read_range_mm_auto_scaling_blocking_direct()
+    }
+
     /// Same functionality as [`start_ambient_continuous_mode()`](VL6180X::start_ambient_continuous_mode)
     /// but with a check on the current [OperatingMode].
     /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
@@ -111,6 +218,18 @@ where
         Ok(())
     }
 
+    /// Same functionality as [`read_ambient_lux_auto_gain_blocking()`](VL6180X::read_ambient_lux_auto_gain_blocking)
+    /// but with a check on the current [OperatingMode].
+    /// Valid when OperatingMode is [AmbientContinuous], otherwise returns [Error::InvalidMethod]
+    pub fn try_read_ambient_lux_auto_gain_blocking(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        if self.mode.operating_mode != AmbientContinuous {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.read_ambient_lux_auto_gain_blocking_direct()
+    }
+
     /// Same functionality as [`start_interleaved_continuous_mode()`](VL6180X::start_interleaved_continuous_mode)
     /// but with a check on the current [OperatingMode].
     /// Valid when OperatingMode is [Ready], otherwise returns [Error::InvalidMethod]
@@ -159,7 +278,7 @@ where
         if self.mode.operating_mode == PoweredOff {
             return Err(Error::InvalidMethod(self.mode.operating_mode));
         }
-        self.read_range_mm_blocking_direct()
+        self.read_range_mm_blocking_direct(self.mode.interleaved())
     }
 
     /// Same functionality as [`read_range_mm()`](VL6180X::read_range_mm)
@@ -173,6 +292,50 @@ where
         self.read_range_mm_direct()
     }
 
+    /// Same functionality as [`read_range_detailed_blocking()`](VL6180X::read_range_detailed_blocking)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_read_range_detailed_blocking(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.read_range_detailed_blocking_direct(self.mode.interleaved())
+    }
+
+    /// Same functionality as [`read_range_detailed()`](VL6180X::read_range_detailed)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_read_range_detailed(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.read_range_detailed_direct()
+    }
+
+    /// Same functionality as [`read_range_history_mm()`](VL6180X::read_range_history_mm)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_read_range_history_mm(&mut self) -> Result<[u16; RANGE_HISTORY_LEN], Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.read_range_history_mm_direct()
+    }
+
+    /// Same functionality as [`read_ambient_history()`](VL6180X::read_ambient_history)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_read_ambient_history(&mut self) -> Result<[u16; AMBIENT_HISTORY_LEN], Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.read_ambient_history_direct()
+    }
+
     /// Same functionality as [`read_ambient_lux_blocking()`](VL6180X::read_ambient_lux_blocking)
     /// but with a check on the current [OperatingMode].
     /// Valid in all OperatingModes except [PoweredOff],
@@ -181,7 +344,7 @@ where
         if self.mode.operating_mode == PoweredOff {
             return Err(Error::InvalidMethod(self.mode.operating_mode));
         }
-        self.read_ambient_lux_blocking_direct()
+        self.read_ambient_lux_blocking_direct(self.mode.interleaved())
     }
 
     /// Same functionality as [`read_ambient_lux()`](VL6180X::read_ambient_lux)
@@ -203,7 +366,7 @@ where
         if self.mode.operating_mode == PoweredOff {
             return Err(Error::InvalidMethod(self.mode.operating_mode));
         }
-        self.read_ambient_blocking_direct()
+        self.read_ambient_blocking_direct(self.mode.interleaved())
     }
 
     /// Same functionality as [`read_ambient()`](VL6180X::read_ambient)
@@ -217,6 +380,17 @@ where
         self.read_ambient_direct()
     }
 
+    /// Same functionality as [`read_device_info()`](VL6180X::read_device_info)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_read_device_info(&mut self) -> Result<DeviceInfo, Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.read_device_info_direct()
+    }
+
     /// Same functionality as [`clear_error_interrupt()`](VL6180X::clear_error_interrupt)
     /// but with a check on the current [OperatingMode].
     /// Valid in all OperatingModes except [PoweredOff],
@@ -250,6 +424,17 @@ where
         self.clear_range_interrupt_direct()
     }
 
+    /// Same functionality as [`clear_history_buffer()`](VL6180X::clear_history_buffer)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_clear_history_buffer(&mut self) -> Result<(), Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.clear_history_buffer_direct()
+    }
+
     /// Same functionality as [`clear_all_interrupts()`](VL6180X::clear_all_interrupts)
     /// but with a check on the current [OperatingMode].
     /// Valid in all OperatingModes except [PoweredOff],
@@ -272,6 +457,43 @@ where
         self.change_i2c_address_direct(new_address)
     }
 
+    /// Same functionality as [`apply_interrupt_thresholds()`](VL6180X::apply_interrupt_thresholds)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_apply_interrupt_thresholds(&mut self, config: &Config) -> Result<(), Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        Ok(self.apply_interrupt_thresholds_direct(config)?)
+    }
+
+    /// Same functionality as [`set_range_thresholds_mm()`](VL6180X::set_range_thresholds_mm)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_set_range_thresholds_mm(
+        &mut self,
+        low_mm: u16,
+        high_mm: u16,
+    ) -> Result<(), Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.set_range_thresholds_mm_direct(low_mm, high_mm)
+    }
+
+    /// Same functionality as [`set_ambient_thresholds()`](VL6180X::set_ambient_thresholds)
+    /// but with a check on the current [OperatingMode].
+    /// Valid in all OperatingModes except [PoweredOff],
+    /// in which case will return [Error::InvalidMethod]
+    pub fn try_set_ambient_thresholds(&mut self, low: u16, high: u16) -> Result<(), Error<E>> {
+        if self.mode.operating_mode == PoweredOff {
+            return Err(Error::InvalidMethod(self.mode.operating_mode));
+        }
+        self.set_ambient_thresholds_direct(low, high)
+    }
+
     /// Same functionality as [`power_off()`](VL6180X::power_off)
     /// but with a check on the current [OperatingMode].
     /// Valid in all OperatingModes except [PoweredOff],
@@ -292,14 +514,15 @@ where
     /// but with a check on the current [OperatingMode].
     /// Valid when OperatingMode is [PoweredOff],
     /// otherwise returns [Error::InvalidMethod]
-    pub fn try_power_on_and_init<PE, P: OutputPin<Error = PE>>(
+    pub fn try_power_on_and_init<PE, P: OutputPin<Error = PE>, D: DelayNs>(
         &mut self,
         x_shutdown_pin: &mut P,
+        delay: &mut D,
     ) -> Result<(), Error2<E, PE>> {
         if self.mode.operating_mode != PoweredOff {
             return Err(Error2::InvalidMethod(self.mode.operating_mode));
         }
-        self.power_on_and_init_direct(x_shutdown_pin)?;
+        self.power_on_and_init_direct(x_shutdown_pin, delay)?;
         self.mode.operating_mode = Ready;
         Ok(())
     }
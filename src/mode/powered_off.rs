@@ -1,9 +1,6 @@
-use embedded_hal::{
-    blocking::i2c::{Write, WriteRead},
-    digital::v2::OutputPin,
-};
+use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
 
-use crate::{error::Error2, mode::ReadyMode, VL6180X};
+use crate::{error::Error2, mode::ReadyMode, BusyWait, Config, WaitStrategy, VL6180X};
 
 /// Mode in which the sensor is powered off.
 #[derive(Debug, Copy, Clone)]
@@ -11,15 +8,47 @@ pub struct PoweredOffMode {}
 
 impl<I2C, E> VL6180X<PoweredOffMode, I2C>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
 {
+    /// Create a driver for a sensor that is powered off, busy polling for measurement results.
+    ///
+    /// Does not communicate with the sensor, so a driver can be rebuilt from a
+    /// [released](VL6180X::release) bus and a saved `config`.
+    /// The sensor is initialized with `config` by [power_on_and_init](VL6180X::power_on_and_init).
+    pub fn new_powered_off(i2c: I2C, config: &Config) -> Self {
+        VL6180X::new_powered_off_with_wait_strategy(i2c, config, BusyWait::default())
+    }
+}
+
+impl<I2C, E, W> VL6180X<PoweredOffMode, I2C, W>
+where
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
+{
+    /// Create a driver for a sensor that is powered off, using `wait` between polls of the
+    /// sensor while waiting for a measurement result, see
+    /// [new_powered_off](VL6180X::new_powered_off).
+    pub fn new_powered_off_with_wait_strategy(i2c: I2C, config: &Config, wait: W) -> Self {
+        Self {
+            mode: PoweredOffMode {},
+            com: i2c,
+            config: *config,
+            wait,
+        }
+    }
+
     /// Powers on the sensor by setting the `x_shutdown_pin` high.
-    /// It then busy waits for the device to be booted and initializes the device.
-    pub fn power_on_and_init<PE, P: OutputPin<Error = PE>>(
+    /// It then waits for the device to be booted, using `delay` to wait between polls,
+    /// and initializes the device.
+    ///
+    /// Returns [Error::BootTimeout](crate::Error::BootTimeout) if the device has not booted
+    /// within the [boot timeout](crate::Config::set_boot_timeout_us).
+    pub fn power_on_and_init<PE, P: OutputPin<Error = PE>, D: DelayNs>(
         mut self,
         x_shutdown_pin: &mut P,
-    ) -> Result<VL6180X<ReadyMode, I2C>, Error2<E, PE>> {
-        self.power_on_and_init_direct(x_shutdown_pin)?;
+        delay: &mut D,
+    ) -> Result<VL6180X<ReadyMode, I2C, W>, Error2<E, PE>> {
+        self.power_on_and_init_direct(x_shutdown_pin, delay)?;
         Ok(self.into_mode(ReadyMode))
     }
 }
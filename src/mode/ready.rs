@@ -1,6 +1,9 @@
-use crate::{error::Error, Config};
+use crate::{
+    error::Error, AmbientMeasurement, BusyWait, Config, DelayWait, RangeMeasurement,
+    ScaledRangeMeasurement, WaitStrategy,
+};
 use crate::{AllowCommunication, VL6180X};
-use embedded_hal::blocking::i2c::{Write, WriteRead};
+use embedded_hal::{delay::DelayNs, i2c::I2c};
 
 use super::{
     AllowReadMeasurement, AllowStartAmbientSingle, AllowStartRangeSingle, AmbientContinuousMode,
@@ -21,20 +24,52 @@ impl AllowStartAmbientSingle for ReadyMode {}
 
 impl<I2C, E> VL6180X<ReadyMode, I2C>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
 {
-    /// Create a new VL6180X driver
+    /// Create a new VL6180X driver, busy polling for measurement results
     pub fn new(i2c: I2C) -> Result<Self, Error<E>> {
         let default_config = &Config::new();
-        VL6180X::with_config(i2c, &default_config)
+        VL6180X::with_config(i2c, default_config)
     }
 
-    /// Create a new VL6180X driver cloning provided config values
+    /// Create a new VL6180X driver cloning provided config values,
+    /// busy polling for measurement results
     pub fn with_config(i2c: I2C, config: &Config) -> Result<Self, Error<E>> {
+        VL6180X::with_wait_strategy(i2c, config, BusyWait::default())
+    }
+}
+
+impl<I2C, E, D> VL6180X<ReadyMode, I2C, DelayWait<D>>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Create a new VL6180X driver, waiting with `delay` between polls for measurement
+    /// results, see [DelayWait]. The measurement timeouts are then in real time.
+    /// Use [with_wait_strategy](VL6180X::with_wait_strategy) to also provide a config.
+    pub fn new_with_delay(i2c: I2C, delay: D) -> Result<Self, Error<E>> {
+        VL6180X::with_wait_strategy(i2c, &Config::new(), DelayWait::new(delay))
+    }
+}
+
+impl<I2C, E, W> VL6180X<ReadyMode, I2C, W>
+where
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
+{
+    /// Create a new VL6180X driver cloning provided config values, using `wait` between
+    /// polls of the sensor while waiting for a measurement result.
+    ///
+    /// ```ignore
+    /// let wait = vl6180x::DelayWait::new(delay);
+    /// let mut tof = vl6180x::VL6180X::with_wait_strategy(i2c, &Config::new(), wait).expect("vl");
+    /// ```
+    pub fn with_wait_strategy(i2c: I2C, config: &Config, wait: W) -> Result<Self, Error<E>> {
         let mut chip = Self {
             mode: ReadyMode,
             com: i2c,
-            config: config.clone(),
+            config: *config,
+            wait,
         };
         let chip_id = chip.read_model_id_direct()?;
         if chip_id == 0xB4 {
@@ -52,24 +87,144 @@ where
     /// kept track of at runtime, allowing you to change the mode often,
     /// and without problems with ownership, or references, at the cost of some
     /// performance and the risk of runtime errors.
-    pub fn into_dynamic_mode(self) -> VL6180X<DynamicMode, I2C> {
+    pub fn into_dynamic_mode(self) -> VL6180X<DynamicMode, I2C, W> {
         self.into_mode(DynamicMode::new())
     }
 
     /// Poll the sensor for a single range measurement.
     /// Starts a single range measurement then calls [`read_range_mm_blocking`](VL6180X::read_range_mm_blocking)
     /// to wait for the result.
+    ///
+    /// Returns [Error::Timeout] if the measurement has not finished within the pre-calibration
+    /// time, max convergence time and readout averaging period of the [Config].
     pub fn poll_range_mm_single_blocking(&mut self) -> Result<u16, Error<E>> {
         self.poll_range_mm_single_blocking_direct()
     }
 
+    /// Poll the sensor for a single range measurement together with its signal diagnostics.
+    /// Starts a single range measurement then calls
+    /// [`read_range_detailed_blocking`](VL6180X::read_range_detailed_blocking) to wait for the result.
+    pub fn poll_range_detailed_single_blocking(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        self.poll_range_detailed_single_blocking_direct()
+    }
+
+    /// Poll the sensor for a single range measurement, adjusting the
+    /// [range scaling](crate::Config::set_range_result_scaler) to the distance.
+    ///
+    /// When the measurement overflows the scaling is raised one step and the measurement
+    /// repeated. When the range is below 80% of the full range of a lower scaling, the
+    /// lowest such scaling is picked and the measurement repeated for the finer
+    /// resolution. The margin up to the full range keeps the scaling from switching back
+    /// and forth at the boundary.
+    ///
+    /// The scaling is kept for later measurements, and returned with the range. The range
+    /// offset, crosstalk valid height and the range settings given in mm are rewritten for
+    /// the new scaling. Scalings these settings can't be represented with are skipped.
+    pub fn poll_range_mm_single_auto_scaling_blocking(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        self.poll_range_mm_single_auto_scaling_blocking_direct()
+    }
+
     /// Poll the sensor for a single ambient light measurement.
     /// Starts a single ambient measurement then calls [`read_ambient_lux_blocking`](VL6180X::read_ambient_lux_blocking)
     /// to wait for the result.
+    ///
+    /// Returns [Error::Timeout] if the measurement has not finished within the integration
+    /// period and readout averaging period of the [Config].
     pub fn poll_ambient_lux_single_blocking(&mut self) -> Result<f32, Error<E>> {
         self.poll_ambient_lux_single_blocking_direct()
     }
 
+    /// Poll the sensor for a single ambient light measurement, adjusting the analogue gain
+    /// to the light level.
+    ///
+    /// When the measurement overflows, or the count is too low or too high for a good
+    /// reading, the [analogue gain level](crate::Config::set_ambient_analogue_gain_level)
+    /// is changed and the measurement repeated, at most once per gain level. The gain is
+    /// kept for later measurements, and returned with the lux value. Ambient interrupt
+    /// thresholds set in lux are rewritten for the new gain.
+    pub fn poll_ambient_lux_single_auto_gain_blocking(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        self.poll_ambient_lux_single_auto_gain_blocking_direct()
+    }
+
+    /// Calibrate the part-to-part range offset, following ST application note AN4545
+    /// section "Offset calibration".
+    ///
+    /// Place a white (88% reflectance) target at `target_distance_mm` from the sensor, 50mm is
+    /// recommended. `samples` single range measurements are taken without any offset applied,
+    /// and the offset is the difference between the target distance and their average.
+    ///
+    /// The offset is written to the sensor and kept in the driver config, and is returned
+    /// so it can be stored and applied on later boots with
+    /// [set_range_offset_mm](crate::config::Config::set_range_offset_mm). If a measurement
+    /// fails, the previous offset is written back before the error is returned.
+    pub fn calibrate_range_offset(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<i8, Error<E>> {
+        self.calibrate_range_offset_direct(target_distance_mm, samples)
+    }
+
+    /// Calibrate the range crosstalk compensation for a cover window, following
+    /// ST application note AN4545 section "Crosstalk calibration".
+    ///
+    /// Place a dark (3% reflectance) target at `target_distance_mm` from the sensor, 100mm is
+    /// recommended. The part-to-part offset should have been calibrated beforehand.
+    /// `samples` single range measurements are taken without compensation, and the
+    /// compensation rate is computed from their average range and return rate.
+    ///
+    /// The rate is written to the sensor and kept in the driver config, and is returned
+    /// so it can be stored and applied on later boots with
+    /// [set_range_crosstalk_compensation_rate](crate::config::Config::set_range_crosstalk_compensation_rate).
+    /// If a measurement fails, the previous rate is written back before the error is returned.
+    pub fn calibrate_crosstalk(
+        &mut self,
+        target_distance_mm: u16,
+        samples: u8,
+    ) -> Result<u16, Error<E>> {
+        self.calibrate_crosstalk_direct(target_distance_mm, samples)
+    }
+
+    /// Calibrate the conversion of ambient light measurements to lux, e.g. for a
+    /// tinted cover window.
+    ///
+    /// Light the sensor evenly with a known illuminance of `reference_lux`, measured with a
+    /// reference lux meter. `samples` single ambient light measurements are taken with
+    /// the current gain and integration period, and the lux resolution factor is scaled
+    /// so their average reads `reference_lux`. Returns
+    /// [Error::InvalidConfigurationValue] if no light was measured.
+    ///
+    /// The factor is kept in the driver config, and is returned so it can be stored and
+    /// applied on later boots with
+    /// [set_ambient_lux_resolution_factor](crate::config::Config::set_ambient_lux_resolution_factor).
+    pub fn calibrate_ambient_lux(
+        &mut self,
+        reference_lux: f32,
+        samples: u8,
+    ) -> Result<f32, Error<E>> {
+        self.calibrate_ambient_lux_direct(reference_lux, samples)
+    }
+
+    /// Reconfigure the sensor with `config` without a power cycle.
+    ///
+    /// Only the registers whose value changes are written, the interrupt mode and
+    /// thresholds within a grouped parameter hold so the sensor applies them together.
+    /// The range scaling is reapplied when it changes, and the sensor is moved to the
+    /// address of `config` if it differs, like [change_i2c_address](VL6180X::change_i2c_address).
+    /// The part-to-part offset read at init is kept unless `config` has a
+    /// [range offset](crate::config::Config::set_range_offset_mm).
+    ///
+    /// Returns [Error::InvalidAddress] or [Error::InvalidConfigurationValue] without
+    /// writing anything if `config` is invalid. After a bus error the sensor may be
+    /// partially reconfigured.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), Error<E>> {
+        self.apply_config_direct(config)
+    }
+
     /// Starts continuous operation mode for reading range measurements.
     ///
     /// Main configuration values are:
@@ -77,7 +232,7 @@ where
     /// 2. [range_max_convergence_time](crate::config::Config::set_range_max_convergence_time())
     pub fn start_range_continuous_mode(
         self,
-    ) -> Result<VL6180X<RangeContinuousMode, I2C>, Error<E>> {
+    ) -> Result<VL6180X<RangeContinuousMode, I2C, W>, Error<E>> {
         let mut new_vl6180x = self.into_mode(RangeContinuousMode {});
         new_vl6180x.toggle_range_continuous_direct()?;
         Ok(new_vl6180x)
@@ -86,7 +241,7 @@ where
     /// Starts continuous operation mode for reading ambient light measurements.
     pub fn start_ambient_continuous_mode(
         self,
-    ) -> Result<VL6180X<AmbientContinuousMode, I2C>, Error<E>> {
+    ) -> Result<VL6180X<AmbientContinuousMode, I2C, W>, Error<E>> {
         let mut new_vl6180x = self.into_mode(AmbientContinuousMode {});
         new_vl6180x.toggle_ambient_continuous_direct()?;
         Ok(new_vl6180x)
@@ -96,7 +251,7 @@ where
     /// The intermeasurement period is set by the [`ambient_inter_measurement_period`](crate::config::Config::set_ambient_inter_measurement_period)
     pub fn start_interleaved_continuous_mode(
         self,
-    ) -> Result<VL6180X<InterleavedContinuousMode, I2C>, Error<E>> {
+    ) -> Result<VL6180X<InterleavedContinuousMode, I2C, W>, Error<E>> {
         let mut new_vl6180x = self.into_mode(InterleavedContinuousMode {});
         new_vl6180x.enable_interleaved_continuous_direct()?;
         Ok(new_vl6180x)
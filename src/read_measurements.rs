@@ -3,28 +3,125 @@ use core::convert::TryFrom;
 use crate::{
     error::Error,
     register::{
-        self, AmbientStatusErrorCode, RangeStatusErrorCode, Register16Bit, Register8Bit,
-        ResultInterruptStatusGpioCode,
+        self, AmbientStatusErrorCode, RangeStatusErrorCode, Register16Bit, Register32Bit,
+        Register8Bit, ResultInterruptStatusGpioCode, AMBIENT_HISTORY_LEN, RANGE_HISTORY_LEN,
     },
-    VL6180X,
+    Config, HistoryBufferMode, WaitStrategy, VL6180X,
 };
-use embedded_hal::blocking::i2c::{Write, WriteRead};
+use embedded_hal::i2c::I2c;
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+#[cfg(test)]
+mod read_measurements_tests;
+
+/// First register of the block of range result registers, see [RangeMeasurement].
+const RANGE_RESULTS_START: u16 = Register8Bit::RESULT__RANGE_VAL as u16;
+/// Length of the block of range result registers, from `RESULT__RANGE_VAL` (0x062)
+/// up to and including `RESULT__RANGE_REFERENCE_CONV_TIME` (0x080 - 0x083).
+pub(crate) const RANGE_RESULTS_LEN: usize = 0x084 - RANGE_RESULTS_START as usize;
+
+/// A range measurement together with the signal diagnostics reported by the sensor.
+///
+/// See VL6180X datasheet section 6.2 RESULT__RANGE_* registers. The diagnostics can be
+/// used to judge the quality of a measurement, e.g. a low return rate relative to the
+/// ambient counts indicates a weak target or strong ambient light.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeMeasurement {
+    /// Range in mm, with the range scaler applied.
+    /// Only meaningful when `status` is [RangeStatusErrorCode::NoError].
+    pub range_mm: u16,
+    /// Raw range before the part-to-part offset and crosstalk compensation are applied,
+    /// in units of the range scaler (RESULT__RANGE_RAW).
+    pub raw_range: u8,
+    /// Error code of the measurement (RESULT__RANGE_STATUS).
+    pub status: RangeStatusErrorCode,
+    /// Return signal rate in Mcps (RESULT__RANGE_RETURN_RATE).
+    pub return_rate_mcps: f32,
+    /// Reference signal rate in Mcps (RESULT__RANGE_REFERENCE_RATE).
+    pub reference_rate_mcps: f32,
+    /// Return signal count (RESULT__RANGE_RETURN_SIGNAL_COUNT).
+    pub return_signal_count: u32,
+    /// Reference signal count (RESULT__RANGE_REFERENCE_SIGNAL_COUNT).
+    pub reference_signal_count: u32,
+    /// Ambient count on the return array (RESULT__RANGE_RETURN_AMB_COUNT).
+    pub return_ambient_count: u32,
+    /// Ambient count on the reference array (RESULT__RANGE_REFERENCE_AMB_COUNT).
+    pub reference_ambient_count: u32,
+    /// Return array convergence time (RESULT__RANGE_RETURN_CONV_TIME).
+    pub return_convergence_time: u32,
+    /// Reference array convergence time (RESULT__RANGE_REFERENCE_CONV_TIME).
+    pub reference_convergence_time: u32,
+}
+
+/// An ambient light measurement taken with automatic gain ranging, see
+/// [poll_ambient_lux_single_auto_gain_blocking](VL6180X::poll_ambient_lux_single_auto_gain_blocking).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientMeasurement {
+    /// Ambient light in lux.
+    pub lux: f32,
+    /// Raw ambient light count (RESULT__ALS_VAL).
+    pub raw_ambient: u16,
+    /// [Analogue gain level](crate::Config::set_ambient_analogue_gain_level) the
+    /// measurement was taken with.
+    pub gain_level: u8,
+}
+
+/// Counts below which automatic gain ranging raises the gain.
+const AUTO_GAIN_MIN_COUNT: u16 = 0x0400;
+/// Counts above which automatic gain ranging lowers the gain.
+const AUTO_GAIN_MAX_COUNT: u16 = 0xC000;
+/// Count automatic gain ranging aims for when it changes the gain.
+const AUTO_GAIN_TARGET_COUNT: f32 = 0x4000 as f32;
+/// Measurements automatic gain ranging takes at most, enough to step through all gain levels.
+pub(crate) const AUTO_GAIN_ATTEMPTS: usize = register::AMBIENT_ANALOGUE_GAIN_VALUE.len();
+
+/// A range measurement taken with automatic range scaling, see
+/// [poll_range_mm_single_auto_scaling_blocking](VL6180X::poll_range_mm_single_auto_scaling_blocking).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScaledRangeMeasurement {
+    /// Range in mm.
+    pub range_mm: u16,
+    /// [Range scaling](crate::Config::set_range_result_scaler) the measurement was taken
+    /// with, the resolution in mm.
+    pub scaling: u8,
+}
+
+/// Share of the full range of a lower scaling, in percent, below which automatic range
+/// scaling switches down to it. The margin up to the full range is the hysteresis.
+const AUTO_SCALING_DOWN_PERCENT: u32 = 80;
+/// Measurements automatic range scaling takes at most, enough to step up through all scalings.
+pub(crate) const AUTO_SCALING_ATTEMPTS: usize = register::RANGE_SCALAR_CODE.len() - 1;
+
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
-    pub(crate) fn read_range_mm_blocking_direct(&mut self) -> Result<u16, Error<E>> {
-        let mut c = 0;
+    /// Waits until `look_for` is no longer reported in the interrupt status,
+    /// using the [WaitStrategy] between polls until `timeout_us` has passed.
+    pub(crate) fn wait_for_event(
+        &mut self,
+        look_for: ResultInterruptStatusGpioCode,
+        timeout_us: u32,
+    ) -> Result<(), Error<E>> {
+        let mut waited_us: u32 = 0;
         while ResultInterruptStatusGpioCode::has_status(
-            ResultInterruptStatusGpioCode::NoRangeEvents,
+            look_for,
             self.read_named_register(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO)?,
         ) {
-            c += 1;
-            if c == self.config.poll_max_loop {
+            if waited_us >= timeout_us {
                 return Err(Error::Timeout);
             }
+            waited_us = waited_us.saturating_add(self.wait.wait(timeout_us - waited_us));
         }
+        Ok(())
+    }
+
+    pub(crate) fn read_range_mm_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<u16, Error<E>> {
+        let timeout_us = range_read_timeout_us(&self.config, interleaved);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)?;
 
         self.get_range_val_and_status()
     }
@@ -41,7 +138,7 @@ where
         self.get_range_val_and_status()
     }
 
-    fn get_range_val_and_status(&mut self) -> Result<u16, Error<E>> {
+    pub(crate) fn get_range_val_and_status(&mut self) -> Result<u16, Error<E>> {
         let status = self.read_named_register(Register8Bit::RESULT__RANGE_STATUS)?;
         self.clear_range_interrupt_direct()?;
         let error = RangeStatusErrorCode::try_from(status)
@@ -50,26 +147,65 @@ where
             return Err(Error::RangeStatusError(error));
         }
         let raw_range = self.read_named_register(Register8Bit::RESULT__RANGE_VAL)?;
-        Ok(self.convert_raw_range_to_mm(raw_range))
+        Ok(convert_raw_range_to_mm(&self.config, raw_range))
     }
 
-    fn convert_raw_range_to_mm(&self, raw_range: u8) -> u16 {
-        self.config.range_scaling as u16 * raw_range as u16
+    pub(crate) fn read_range_detailed_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<RangeMeasurement, Error<E>> {
+        let timeout_us = range_read_timeout_us(&self.config, interleaved);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)?;
+
+        self.get_range_detailed()
     }
 
-    pub(crate) fn read_ambient_lux_blocking_direct(&mut self) -> Result<f32, Error<E>> {
-        let mut c = 0;
-        while ResultInterruptStatusGpioCode::has_status(
-            ResultInterruptStatusGpioCode::NoAmbientEvents,
+    pub(crate) fn read_range_detailed_direct(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        if ResultInterruptStatusGpioCode::has_status(
+            ResultInterruptStatusGpioCode::NoRangeEvents,
             self.read_named_register(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO)?,
         ) {
-            c += 1;
-            if c == self.config.poll_max_loop {
-                return Err(Error::Timeout);
-            }
+            return Err(Error::ResultNotReady);
         }
+        self.get_range_detailed()
+    }
+
+    /// Reads all range result registers in one transfer before clearing the interrupt,
+    /// so the values all belong to the same measurement.
+    pub(crate) fn get_range_detailed(&mut self) -> Result<RangeMeasurement, Error<E>> {
+        let status = self.read_named_register(Register8Bit::RESULT__RANGE_STATUS)?;
+        let mut results = [0u8; RANGE_RESULTS_LEN];
+        self.read_bytes(RANGE_RESULTS_START, &mut results)?;
+        self.clear_range_interrupt_direct()?;
+        parse_range_measurement(&self.config, status, &results)
+    }
+
+    pub(crate) fn read_range_history_mm_direct(
+        &mut self,
+    ) -> Result<[u16; RANGE_HISTORY_LEN], Error<E>> {
+        check_history_buffer_mode(&self.config, HistoryBufferMode::Range)?;
+        let mut history = [0u8; RANGE_HISTORY_LEN];
+        self.read_bytes(Register16Bit::RESULT__HISTORY_BUFFER_0 as u16, &mut history)?;
+        Ok(history.map(|raw_range| convert_raw_range_to_mm(&self.config, raw_range)))
+    }
+
+    pub(crate) fn read_ambient_history_direct(
+        &mut self,
+    ) -> Result<[u16; AMBIENT_HISTORY_LEN], Error<E>> {
+        check_history_buffer_mode(&self.config, HistoryBufferMode::Ambient)?;
+        let mut history = [0u8; AMBIENT_HISTORY_LEN * 2];
+        self.read_bytes(Register16Bit::RESULT__HISTORY_BUFFER_0 as u16, &mut history)?;
+        Ok(parse_ambient_history(&history))
+    }
+
+    pub(crate) fn read_ambient_lux_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<f32, Error<E>> {
+        let timeout_us = ambient_read_timeout_us(&self.config, interleaved);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoAmbientEvents, timeout_us)?;
         let raw_ambient = self.get_ambient_val_and_status()?;
-        Ok(self.convert_raw_ambient_to_lux(raw_ambient))
+        Ok(convert_raw_ambient_to_lux(&self.config, raw_ambient))
     }
 
     pub(crate) fn read_ambient_lux_direct(&mut self) -> Result<f32, Error<E>> {
@@ -80,20 +216,15 @@ where
             return Err(Error::ResultNotReady);
         }
         let raw_ambient = self.get_ambient_val_and_status()?;
-        Ok(self.convert_raw_ambient_to_lux(raw_ambient))
+        Ok(convert_raw_ambient_to_lux(&self.config, raw_ambient))
     }
 
-    pub(crate) fn read_ambient_blocking_direct(&mut self) -> Result<u16, Error<E>> {
-        let mut c = 0;
-        while ResultInterruptStatusGpioCode::has_status(
-            ResultInterruptStatusGpioCode::NoAmbientEvents,
-            self.read_named_register(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO)?,
-        ) {
-            c += 1;
-            if c == self.config.poll_max_loop {
-                return Err(Error::Timeout);
-            }
-        }
+    pub(crate) fn read_ambient_blocking_direct(
+        &mut self,
+        interleaved: bool,
+    ) -> Result<u16, Error<E>> {
+        let timeout_us = ambient_read_timeout_us(&self.config, interleaved);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoAmbientEvents, timeout_us)?;
         self.get_ambient_val_and_status()
     }
 
@@ -107,7 +238,63 @@ where
         self.get_ambient_val_and_status()
     }
 
-    fn get_ambient_val_and_status(&mut self) -> Result<u16, Error<E>> {
+    pub(crate) fn read_range_mm_auto_scaling_blocking_direct(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.read_range_mm_blocking_direct(false);
+            match auto_range_scaling(&self.config, &result) {
+                Some(scaling) if attempts < AUTO_SCALING_ATTEMPTS => {
+                    self.set_range_scaling_direct(scaling)?;
+                    // The sample in progress was started with the previous scaling
+                    discard_measurement(self.read_range_mm_blocking_direct(false))?;
+                    attempts += 1;
+                }
+                _ => {
+                    return Ok(ScaledRangeMeasurement {
+                        range_mm: result?,
+                        scaling: self.config.range_scaling,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Changes the range scaling, re-applying the range offset, the crosstalk valid height
+    /// and the range settings given in mm.
+    pub(crate) fn set_range_scaling_direct(&mut self, scaling: u8) -> Result<(), E> {
+        let mut config = self.config;
+        config.range_scaling = scaling;
+        self.write_changed_registers(&config)
+    }
+
+    pub(crate) fn read_ambient_lux_auto_gain_blocking_direct(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.read_ambient_blocking_direct(false);
+            match auto_gain_level(self.config.ambient_analogue_gain_level, &result) {
+                Some(level) if attempts < AUTO_GAIN_ATTEMPTS => {
+                    self.set_ambient_gain_level_direct(level)?;
+                    // The sample in progress was started with the previous gain
+                    discard_measurement(self.read_ambient_blocking_direct(false))?;
+                    attempts += 1;
+                }
+                _ => return Ok(ambient_measurement(&self.config, result?)),
+            }
+        }
+    }
+
+    /// Changes the analogue gain level, rewriting the ambient thresholds set in lux.
+    pub(crate) fn set_ambient_gain_level_direct(&mut self, level: u8) -> Result<(), E> {
+        let mut config = self.config;
+        config.ambient_analogue_gain_level = level;
+        self.write_changed_registers(&config)
+    }
+
+    pub(crate) fn get_ambient_val_and_status(&mut self) -> Result<u16, Error<E>> {
         let status = self.read_named_register(Register8Bit::RESULT__ALS_STATUS)?;
         self.clear_ambient_interrupt_direct()?;
         let error = AmbientStatusErrorCode::try_from(status)
@@ -118,16 +305,227 @@ where
         let raw_ambient = self.read_named_register_16bit(Register16Bit::RESULT__ALS_VAL)?;
         Ok(raw_ambient)
     }
+}
+
+pub(crate) fn parse_range_measurement<E>(
+    config: &Config,
+    status: u8,
+    results: &[u8; RANGE_RESULTS_LEN],
+) -> Result<RangeMeasurement, Error<E>> {
+    let status =
+        RangeStatusErrorCode::try_from(status).map_err(|_| Error::UnknownRegisterCode(status))?;
+
+    let at = |reg: u16| (reg - RANGE_RESULTS_START) as usize;
+    let u8_at = |reg: Register8Bit| results[at(reg as u16)];
+    let u16_at = |reg: Register16Bit| {
+        let i = at(reg as u16);
+        u16::from_be_bytes([results[i], results[i + 1]])
+    };
+    let u32_at = |reg: Register32Bit| {
+        let i = at(reg as u16);
+        u32::from_be_bytes([results[i], results[i + 1], results[i + 2], results[i + 3]])
+    };
+
+    Ok(RangeMeasurement {
+        range_mm: convert_raw_range_to_mm(config, u8_at(Register8Bit::RESULT__RANGE_VAL)),
+        raw_range: u8_at(Register8Bit::RESULT__RANGE_RAW),
+        status,
+        return_rate_mcps: convert_rate_to_mcps(u16_at(Register16Bit::RESULT__RANGE_RETURN_RATE)),
+        reference_rate_mcps: convert_rate_to_mcps(u16_at(
+            Register16Bit::RESULT__RANGE_REFERENCE_RATE,
+        )),
+        return_signal_count: u32_at(Register32Bit::RESULT__RANGE_RETURN_SIGNAL_COUNT),
+        reference_signal_count: u32_at(Register32Bit::RESULT__RANGE_REFERENCE_SIGNAL_COUNT),
+        return_ambient_count: u32_at(Register32Bit::RESULT__RANGE_RETURN_AMB_COUNT),
+        reference_ambient_count: u32_at(Register32Bit::RESULT__RANGE_REFERENCE_AMB_COUNT),
+        return_convergence_time: u32_at(Register32Bit::RESULT__RANGE_RETURN_CONV_TIME),
+        reference_convergence_time: u32_at(Register32Bit::RESULT__RANGE_REFERENCE_CONV_TIME),
+    })
+}
+
+pub(crate) fn check_history_buffer_mode<E>(
+    config: &Config,
+    mode: HistoryBufferMode,
+) -> Result<(), Error<E>> {
+    if config.history_buffer_mode != mode {
+        return Err(Error::InvalidConfigurationValue(
+            config.history_buffer_mode as u16,
+        ));
+    }
+    Ok(())
+}
+
+/// RESULT__HISTORY_BUFFER_0 holds the most recent measurement.
+pub(crate) fn parse_ambient_history(
+    history: &[u8; AMBIENT_HISTORY_LEN * 2],
+) -> [u16; AMBIENT_HISTORY_LEN] {
+    let mut ambient = [0u16; AMBIENT_HISTORY_LEN];
+    for (value, bytes) in ambient.iter_mut().zip(history.chunks_exact(2)) {
+        *value = u16::from_be_bytes([bytes[0], bytes[1]]);
+    }
+    ambient
+}
+
+/// Rates are reported in 9.7 fixed point Mcps.
+fn convert_rate_to_mcps(rate: u16) -> f32 {
+    rate as f32 / 128.0
+}
+
+pub(crate) fn convert_raw_range_to_mm(config: &Config, raw_range: u8) -> u16 {
+    config.range_scaling as u16 * raw_range as u16
+}
+
+pub(crate) fn convert_raw_ambient_to_lux(config: &Config, raw_ambient: u16) -> f32 {
+    let analogue_gain =
+        register::AMBIENT_ANALOGUE_GAIN_VALUE[config.ambient_analogue_gain_level as usize];
+
+    let integration_period = config.ambient_integration_period;
+
+    (config.ambient_lux_resolution_factor * 100.0 / analogue_gain)
+        * (raw_ambient as f32 / integration_period as f32)
+}
+
+/// Inverse of [convert_raw_ambient_to_lux], the raw ambient value measured at `lux`.
+pub(crate) fn convert_lux_to_raw_ambient(config: &Config, lux: f32) -> f32 {
+    let analogue_gain =
+        register::AMBIENT_ANALOGUE_GAIN_VALUE[config.ambient_analogue_gain_level as usize];
+
+    let integration_period = config.ambient_integration_period;
+
+    lux * integration_period as f32 / (config.ambient_lux_resolution_factor * 100.0 / analogue_gain)
+}
+
+/// Drops the result of a measurement taken with outdated settings, only passing on
+/// bus errors and timeouts.
+pub(crate) fn discard_measurement<T, E>(result: Result<T, Error<E>>) -> Result<(), Error<E>> {
+    match result {
+        Ok(_) | Err(Error::RangeStatusError(_)) | Err(Error::AmbientStatusError(_)) => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Gain level to repeat an ambient measurement with, `None` if the `result` measured at
+/// `level` is usable or no better gain level exists.
+///
+/// After an overflow the gain is lowered one level. Otherwise, when the count is outside
+/// the usable range, the level with a count closest to below the target is picked,
+/// assuming the count scales with the gain.
+pub(crate) fn auto_gain_level<E>(level: u8, result: &Result<u16, Error<E>>) -> Option<u8> {
+    let count = match result {
+        Ok(count) => *count,
+        Err(Error::AmbientStatusError(AmbientStatusErrorCode::Overflow)) => {
+            return level.checked_sub(1)
+        }
+        Err(Error::AmbientStatusError(AmbientStatusErrorCode::Underflow)) => 0,
+        Err(_) => return None,
+    };
+    if (AUTO_GAIN_MIN_COUNT..=AUTO_GAIN_MAX_COUNT).contains(&count) {
+        return None;
+    }
+    let gains = register::AMBIENT_ANALOGUE_GAIN_VALUE;
+    let count_per_gain = count as f32 / gains[level as usize];
+    let best = (0..gains.len())
+        .rev()
+        .find(|&l| count_per_gain * gains[l] <= AUTO_GAIN_TARGET_COUNT)
+        .unwrap_or(0) as u8;
+    if best == level {
+        None
+    } else {
+        Some(best)
+    }
+}
+
+/// Range scaling to repeat a range measurement with, `None` if the `result` measured with
+/// the scaling of `config` is usable or no other scaling is allowed.
+///
+/// After an overflow the scaling is raised one step. A range well within the full range
+/// of a lower scaling switches to the lowest such scaling. Only scalings the range
+/// settings in mm of `config` can be represented with are picked.
+pub(crate) fn auto_range_scaling<E>(config: &Config, result: &Result<u16, Error<E>>) -> Option<u8> {
+    let allowed = |scaling: u8| {
+        let mut config = *config;
+        config.set_range_result_scaler(scaling).is_ok()
+    };
+    match result {
+        Err(Error::RangeStatusError(
+            RangeStatusErrorCode::RangingAlgoOverflow
+            | RangeStatusErrorCode::RawRangingAlgoOverflow,
+        )) => Some(config.range_scaling + 1).filter(|&scaling| allowed(scaling)),
+        Ok(range_mm) => (1..config.range_scaling).find(|&scaling| {
+            (*range_mm as u32) * 100 < 0xFF * scaling as u32 * AUTO_SCALING_DOWN_PERCENT
+                && allowed(scaling)
+        }),
+        Err(_) => None,
+    }
+}
+
+pub(crate) fn ambient_measurement(config: &Config, raw_ambient: u16) -> AmbientMeasurement {
+    AmbientMeasurement {
+        lux: convert_raw_ambient_to_lux(config, raw_ambient),
+        raw_ambient,
+        gain_level: config.ambient_analogue_gain_level,
+    }
+}
+
+/// Readout averaging sample period in us, see
+/// [set_readout_averaging_period_multiplier](Config::set_readout_averaging_period_multiplier).
+fn readout_averaging_period_us(config: &Config) -> u32 {
+    1300 + 645 * config.readout_averaging_period_multiplier as u32 / 10
+}
+
+/// The measurement periods from the datasheet only hold within the tolerance of the
+/// internal oscillator, which ST accounts for with a factor of 0.9 in the
+/// inter-measurement period requirements.
+fn with_oscillator_margin(time_us: u32) -> u32 {
+    time_us * 10 / 9
+}
 
-    fn convert_raw_ambient_to_lux(&self, raw_ambient: u16) -> f32 {
-        let analogue_gain =
-            register::AMBIENT_ANALOGUE_GAIN_VALUE[self.config.ambient_analogue_gain_level as usize];
+/// Longest time a single range measurement can take:
+/// pre-calibration (3.2ms) + max convergence time + readout averaging period.
+pub(crate) fn range_timeout_us(config: &Config) -> u32 {
+    with_oscillator_margin(
+        3200 + config.range_max_convergence_time as u32 * 1000
+            + readout_averaging_period_us(config),
+    )
+}
 
-        let integration_period = self.config.ambient_integration_period;
+/// Longest time a single ambient light measurement can take:
+/// integration period * 1.1 + readout averaging period.
+pub(crate) fn ambient_timeout_us(config: &Config) -> u32 {
+    with_oscillator_margin(
+        config.ambient_integration_period as u32 * 1100 + readout_averaging_period_us(config),
+    )
+}
 
-        const LUX_RESOLUTION_FACTOR: f32 = 0.32_f32;
+/// Longest time until the next range result when the measurement may have been started
+/// in either single or continuous mode: a full range inter-measurement period, then the
+/// range measurement.
+pub(crate) fn range_read_timeout_us(config: &Config, interleaved: bool) -> u32 {
+    if interleaved {
+        return interleaved_timeout_us(config);
+    }
+    with_oscillator_margin(config.range_inter_measurement_period as u32 * 1000)
+        + range_timeout_us(config)
+}
 
-        (LUX_RESOLUTION_FACTOR * 100.0 / analogue_gain)
-            * (raw_ambient as f32 / integration_period as f32)
+/// Longest time until the next ambient light result when the measurement may have been
+/// started in either single or continuous mode: a full ambient inter-measurement period,
+/// then the ambient light measurement.
+pub(crate) fn ambient_read_timeout_us(config: &Config, interleaved: bool) -> u32 {
+    if interleaved {
+        return interleaved_timeout_us(config);
     }
+    with_oscillator_margin(config.ambient_inter_measurement_period as u32 * 1000)
+        + ambient_timeout_us(config)
+}
+
+/// Longest time until the next result in interleaved mode: a full inter-measurement
+/// period, then the range and ambient measurements, which run one after the other.
+fn interleaved_timeout_us(config: &Config) -> u32 {
+    let period_ms = config
+        .range_inter_measurement_period
+        .max(config.ambient_inter_measurement_period);
+    with_oscillator_margin(period_ms as u32 * 1000)
+        + range_timeout_us(config)
+        + ambient_timeout_us(config)
 }
@@ -0,0 +1,553 @@
+use super::*;
+use crate::sim::SimBus;
+use crate::{DelayWait, OperatingMode};
+
+#[test]
+fn poll_range_detailed_single() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(80);
+    sensor.set_signal_rate(3 << 7);
+    sensor.set_crosstalk_rate(1 << 6);
+    sensor.set_ambient_counts(42);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    let measurement = tof.poll_range_detailed_single_blocking().unwrap();
+    assert_eq!(measurement.status, RangeStatusErrorCode::NoError);
+    assert_eq!(measurement.range_mm, 68);
+    assert_eq!(measurement.raw_range, 68);
+    assert_eq!(measurement.return_rate_mcps, 3.5);
+    assert_eq!(measurement.reference_rate_mcps, 5.0);
+    assert_eq!(measurement.return_signal_count, 3500);
+    assert_eq!(measurement.reference_signal_count, 5000);
+    assert_eq!(measurement.return_ambient_count, 42);
+    assert_eq!(measurement.reference_ambient_count, 42);
+    assert_eq!(measurement.return_convergence_time, 1000);
+    assert_eq!(measurement.reference_convergence_time, 1000);
+}
+
+#[test]
+fn range_detailed_keeps_status_error() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_status(Some(RangeStatusErrorCode::MaxSignalToNoiseRatio));
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    let measurement = tof.poll_range_detailed_single_blocking().unwrap();
+    assert_eq!(
+        measurement.status,
+        RangeStatusErrorCode::MaxSignalToNoiseRatio
+    );
+    assert_eq!(tof.read_range_detailed(), Err(Error::ResultNotReady));
+}
+
+#[test]
+fn range_detailed_applies_scaling_and_offset() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(300);
+    let mut config = Config::new();
+    config.set_range_result_scaler(3).unwrap();
+    config.set_range_offset_mm(6);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    let measurement = tof.poll_range_detailed_single_blocking().unwrap();
+    assert_eq!(measurement.raw_range, 100);
+    assert_eq!(measurement.range_mm, 306);
+}
+
+#[test]
+fn parse_range_measurement_register_layout() {
+    let mut results = [0u8; RANGE_RESULTS_LEN];
+    results[0x062 - 0x062] = 10;
+    results[0x064 - 0x062] = 11;
+    results[0x066 - 0x062..0x068 - 0x062].copy_from_slice(&0x0140u16.to_be_bytes());
+    results[0x068 - 0x062..0x06A - 0x062].copy_from_slice(&0x0020u16.to_be_bytes());
+    results[0x06C - 0x062..0x070 - 0x062].copy_from_slice(&1u32.to_be_bytes());
+    results[0x070 - 0x062..0x074 - 0x062].copy_from_slice(&2u32.to_be_bytes());
+    results[0x074 - 0x062..0x078 - 0x062].copy_from_slice(&3u32.to_be_bytes());
+    results[0x078 - 0x062..0x07C - 0x062].copy_from_slice(&4u32.to_be_bytes());
+    results[0x07C - 0x062..0x080 - 0x062].copy_from_slice(&5u32.to_be_bytes());
+    results[0x080 - 0x062..0x084 - 0x062].copy_from_slice(&0x0102_0304u32.to_be_bytes());
+
+    let measurement = parse_range_measurement::<()>(&Config::new(), 0x71, &results).unwrap();
+    assert_eq!(
+        measurement,
+        RangeMeasurement {
+            range_mm: 10,
+            raw_range: 11,
+            status: RangeStatusErrorCode::MaxConvergence,
+            return_rate_mcps: 2.5,
+            reference_rate_mcps: 0.25,
+            return_signal_count: 1,
+            reference_signal_count: 2,
+            return_ambient_count: 3,
+            reference_ambient_count: 4,
+            return_convergence_time: 5,
+            reference_convergence_time: 0x0102_0304,
+        }
+    );
+}
+
+#[test]
+fn range_history_keeps_last_measurements() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_history_buffer_mode(HistoryBufferMode::Range);
+    let tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    let mut tof = tof.start_range_continuous_mode().unwrap();
+    for range_mm in (10..=180).step_by(10) {
+        sensor.set_range_mm(range_mm);
+        assert_eq!(tof.read_range_mm_blocking(), Ok(range_mm));
+    }
+    let mut expected = [0u16; RANGE_HISTORY_LEN];
+    for (i, value) in expected.iter_mut().enumerate() {
+        *value = 180 - 10 * i as u16;
+    }
+    assert_eq!(tof.read_range_history_mm(), Ok(expected));
+
+    tof.clear_history_buffer().unwrap();
+    assert_eq!(tof.read_range_history_mm(), Ok([0; RANGE_HISTORY_LEN]));
+}
+
+#[test]
+fn ambient_history_keeps_last_measurements() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_history_buffer_mode(HistoryBufferMode::Ambient);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    for counts in [1000, 2000, 3000] {
+        sensor.set_ambient_counts(counts);
+        tof.poll_ambient_lux_single_blocking().unwrap();
+    }
+    // Range measurements are not buffered in ambient mode
+    tof.poll_range_mm_single_blocking().unwrap();
+    assert_eq!(
+        tof.read_ambient_history(),
+        Ok([3000, 2000, 1000, 0, 0, 0, 0, 0])
+    );
+}
+
+#[test]
+fn history_requires_matching_mode() {
+    let (bus, _sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(
+        tof.read_range_history_mm(),
+        Err(Error::InvalidConfigurationValue(
+            HistoryBufferMode::Disabled as u16
+        ))
+    );
+    assert_eq!(
+        tof.read_ambient_history(),
+        Err(Error::InvalidConfigurationValue(
+            HistoryBufferMode::Disabled as u16
+        ))
+    );
+}
+
+#[test]
+fn measurement_timeouts_from_config() {
+    let mut config = Config::new();
+    // (3.2ms + 49ms + 1.3ms + 48 * 64.5us) / 0.9
+    assert_eq!(range_timeout_us(&config), 62_884);
+    // (100ms * 1.1 + 1.3ms + 48 * 64.5us) / 0.9
+    assert_eq!(ambient_timeout_us(&config), 127_106);
+    // 100ms / 0.9 + range
+    assert_eq!(range_read_timeout_us(&config, false), 111_111 + 62_884);
+    // 500ms / 0.9 + ambient
+    assert_eq!(ambient_read_timeout_us(&config, false), 555_555 + 127_106);
+    // 500ms / 0.9 + range + ambient
+    let interleaved_us = 555_555 + 62_884 + 127_106;
+    assert_eq!(range_read_timeout_us(&config, true), interleaved_us);
+    assert_eq!(ambient_read_timeout_us(&config, true), interleaved_us);
+
+    config.set_range_max_convergence_time(20).unwrap();
+    config.set_readout_averaging_period_multiplier(0);
+    assert_eq!(range_timeout_us(&config), 27_222);
+}
+
+#[test]
+fn poll_range_single_times_out_after_measurement_time() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(1_000_000);
+    // Polls take the 50us assumed by the delay wait
+    bus.set_transaction_time_us(50);
+    let wait = DelayWait::new(bus.delay());
+    let mut tof = VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), wait).unwrap();
+    let start_us = bus.now_us();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Err(Error::Timeout));
+    let waited_us = bus.now_us() - start_us;
+    let timeout_us = range_timeout_us(&Config::new()) as u64;
+    assert!(waited_us >= timeout_us);
+    assert!(waited_us < timeout_us + 300);
+}
+
+#[test]
+fn read_range_blocking_waits_for_continuous_period() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(400_000);
+    let mut config = Config::new();
+    config.set_range_inter_measurement_period(500).unwrap();
+    let wait = DelayWait::new(bus.delay());
+    let tof = VL6180X::with_wait_strategy(bus.i2c(), &config, wait).unwrap();
+    let mut tof = tof.start_range_continuous_mode().unwrap();
+    assert_eq!(tof.read_range_mm_blocking(), Ok(100));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_poll_ambient_single_times_out() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(1_000_000);
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        assert_eq!(tof.poll_ambient_lux_single().await, Err(Error::Timeout));
+    });
+}
+
+#[test]
+fn auto_gain_level_steps() {
+    let ok = |count: u16| -> Result<u16, Error<()>> { Ok(count) };
+    let status = |code| -> Result<u16, Error<()>> { Err(Error::AmbientStatusError(code)) };
+
+    assert_eq!(auto_gain_level(0, &ok(0x4000)), None);
+    // Dark: highest gain that keeps the count below the target
+    assert_eq!(auto_gain_level(0, &ok(100)), Some(7));
+    assert_eq!(
+        auto_gain_level(2, &status(AmbientStatusErrorCode::Underflow)),
+        Some(7)
+    );
+    // Bright: 61440 counts at 40x is 15851 at 10.32x
+    assert_eq!(auto_gain_level(7, &ok(0xF000)), Some(5));
+    assert_eq!(auto_gain_level(0, &ok(0xF000)), None);
+    // Overflow only tells the count is too high, lower one level at a time
+    assert_eq!(
+        auto_gain_level(3, &status(AmbientStatusErrorCode::Overflow)),
+        Some(2)
+    );
+    assert_eq!(
+        auto_gain_level(0, &status(AmbientStatusErrorCode::Overflow)),
+        None
+    );
+    assert_eq!(auto_gain_level(3, &Err(Error::<()>::Timeout)), None);
+}
+
+#[test]
+fn poll_ambient_auto_gain_lowers_gain_in_bright_light() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_lux(1000.0);
+    let mut config = Config::new();
+    config.set_ambient_analogue_gain_level(6).unwrap();
+    config
+        .set_ambient_high_interrupt_threshold_lux(500.0)
+        .unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(tof.poll_ambient_single_blocking_direct(), Ok(62_500));
+
+    let measurement = tof.poll_ambient_lux_single_auto_gain_blocking().unwrap();
+    assert_eq!(measurement.gain_level, 4);
+    assert_eq!(measurement.raw_ambient, 16_281);
+    assert!((measurement.lux - 1000.0).abs() < 0.1);
+    assert_eq!(
+        sensor.register(Register8Bit::SYSALS__ANALOGUE_GAIN as u16),
+        register::AMBIENT_ANALOGUE_GAIN_CODE[4]
+    );
+    assert_eq!(
+        sensor.register_16bit(Register16Bit::SYSALS__THRESH_HIGH as u16),
+        8141
+    );
+
+    // The gain is kept for the following measurements
+    let lux = tof.poll_ambient_lux_single_blocking().unwrap();
+    assert!((lux - 1000.0).abs() < 0.1);
+}
+
+#[test]
+fn poll_ambient_auto_gain_steps_down_from_overflow() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_lux(10_000.0);
+    let mut config = Config::new();
+    config.set_ambient_analogue_gain_level(7).unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+
+    let measurement = tof.poll_ambient_lux_single_auto_gain_blocking().unwrap();
+    assert_eq!(measurement.gain_level, 0);
+    assert!((measurement.lux - 10_000.0).abs() < 1.0);
+}
+
+#[test]
+fn poll_ambient_auto_gain_keeps_overflow_at_lowest_gain() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_lux(100_000.0);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(
+        tof.poll_ambient_lux_single_auto_gain_blocking(),
+        Err(Error::AmbientStatusError(AmbientStatusErrorCode::Overflow))
+    );
+}
+
+#[test]
+fn read_ambient_auto_gain_raises_gain_in_the_dark() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_lux(1.0);
+    let tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut tof = tof.start_ambient_continuous_mode().unwrap();
+
+    let measurement = tof.read_ambient_lux_auto_gain_blocking().unwrap();
+    assert_eq!(measurement.gain_level, 7);
+    assert_eq!(measurement.raw_ambient, 125);
+    assert!((measurement.lux - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn read_ambient_auto_gain_drops_sample_with_previous_gain() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_lux(1.0);
+    sensor.set_measurement_time_us(10_000);
+    let tof = VL6180X::new_with_delay(bus.i2c(), bus.delay()).unwrap();
+    let mut tof = tof.start_ambient_continuous_mode().unwrap();
+    let start_us = bus.now_us();
+
+    // The gain is switched while the second sample is taken with the previous gain,
+    // so the third sample is read.
+    let measurement = tof.read_ambient_lux_auto_gain_blocking().unwrap();
+    assert_eq!(measurement.gain_level, 7);
+    assert_eq!(measurement.raw_ambient, 125);
+    assert!(bus.now_us() - start_us >= 30_000);
+}
+
+#[test]
+fn try_ambient_auto_gain_checks_mode() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_lux(1.0);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap().into_dynamic_mode();
+    assert_eq!(
+        tof.try_read_ambient_lux_auto_gain_blocking(),
+        Err(Error::InvalidMethod(OperatingMode::Ready))
+    );
+    assert_eq!(
+        tof.try_poll_ambient_lux_single_auto_gain_blocking()
+            .unwrap()
+            .gain_level,
+        7
+    );
+    tof.try_start_ambient_continuous_mode().unwrap();
+    assert_eq!(
+        tof.try_poll_ambient_lux_single_auto_gain_blocking(),
+        Err(Error::InvalidMethod(OperatingMode::AmbientContinuous))
+    );
+    assert_eq!(
+        tof.try_read_ambient_lux_auto_gain_blocking()
+            .unwrap()
+            .gain_level,
+        7
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_ambient_auto_gain() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_lux(1000.0);
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        let measurement = tof.poll_ambient_lux_single_auto_gain().await.unwrap();
+        assert_eq!(measurement.gain_level, 0);
+        assert!((measurement.lux - 1000.0).abs() < 0.1);
+
+        sensor.set_ambient_lux(1.0);
+        let mut tof = tof.start_ambient_continuous_mode().await.unwrap();
+        let measurement = tof.read_ambient_lux_auto_gain().await.unwrap();
+        assert_eq!(measurement.gain_level, 7);
+        assert!((measurement.lux - 1.0).abs() < 0.01);
+    });
+}
+
+#[test]
+fn auto_range_scaling_steps() {
+    let ok = |range_mm: u16| -> Result<u16, Error<()>> { Ok(range_mm) };
+    let overflow: Result<u16, Error<()>> = Err(Error::RangeStatusError(
+        RangeStatusErrorCode::RangingAlgoOverflow,
+    ));
+    let mut config = Config::new();
+
+    assert_eq!(auto_range_scaling(&config, &ok(200)), None);
+    assert_eq!(auto_range_scaling(&config, &overflow), Some(2));
+
+    config.set_range_result_scaler(2).unwrap();
+    // Hysteresis: switches down below 80% of the 255 mm full range at 1x
+    assert_eq!(auto_range_scaling(&config, &ok(204)), None);
+    assert_eq!(auto_range_scaling(&config, &ok(203)), Some(1));
+
+    config.set_range_result_scaler(3).unwrap();
+    assert_eq!(auto_range_scaling(&config, &ok(100)), Some(1));
+    assert_eq!(auto_range_scaling(&config, &ok(300)), Some(2));
+    assert_eq!(auto_range_scaling(&config, &ok(500)), None);
+    assert_eq!(auto_range_scaling(&config, &overflow), None);
+    assert_eq!(
+        auto_range_scaling(&config, &Err(Error::<()>::Timeout)),
+        None
+    );
+
+    // Scalings the thresholds in mm don't fit are skipped
+    config.set_range_high_interrupt_threshold_mm(500).unwrap();
+    assert_eq!(auto_range_scaling(&config, &ok(100)), Some(2));
+}
+
+#[test]
+fn poll_range_auto_scaling_follows_distance() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_offset_mm(6);
+    config.set_range_high_interrupt_threshold_mm(240).unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+
+    sensor.set_range_mm(400);
+    assert_eq!(
+        tof.poll_range_mm_single_auto_scaling_blocking(),
+        Ok(ScaledRangeMeasurement {
+            range_mm: 406,
+            scaling: 2
+        })
+    );
+    assert_eq!(
+        sensor.register_16bit(Register16Bit::RANGE_SCALER as u16),
+        register::RANGE_SCALAR_CODE[2]
+    );
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16),
+        3
+    );
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__CROSSTALK_VALID_HEIGHT as u16),
+        10
+    );
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__THRESH_HIGH as u16),
+        120
+    );
+
+    // Within the hysteresis the scaling is kept
+    sensor.set_range_mm(214);
+    assert_eq!(
+        tof.poll_range_mm_single_auto_scaling_blocking(),
+        Ok(ScaledRangeMeasurement {
+            range_mm: 220,
+            scaling: 2
+        })
+    );
+
+    sensor.set_range_mm(94);
+    assert_eq!(
+        tof.poll_range_mm_single_auto_scaling_blocking(),
+        Ok(ScaledRangeMeasurement {
+            range_mm: 100,
+            scaling: 1
+        })
+    );
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16),
+        6
+    );
+    assert_eq!(
+        sensor.register(Register8Bit::SYSRANGE__THRESH_HIGH as u16),
+        240
+    );
+}
+
+#[test]
+fn poll_range_auto_scaling_reaches_3x() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    sensor.set_range_mm(700);
+    assert_eq!(
+        tof.poll_range_mm_single_auto_scaling_blocking(),
+        Ok(ScaledRangeMeasurement {
+            range_mm: 699,
+            scaling: 3
+        })
+    );
+    sensor.set_range_mm(800);
+    assert_eq!(
+        tof.poll_range_mm_single_auto_scaling_blocking(),
+        Err(Error::RangeStatusError(
+            RangeStatusErrorCode::RangingAlgoOverflow
+        ))
+    );
+}
+
+#[test]
+fn read_range_auto_scaling_in_continuous_mode() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(300);
+    sensor.set_measurement_time_us(10_000);
+    let wait = DelayWait::new(bus.delay());
+    let tof = VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), wait).unwrap();
+    let mut tof = tof.into_dynamic_mode();
+    assert_eq!(
+        tof.try_read_range_mm_auto_scaling_blocking(),
+        Err(Error::InvalidMethod(OperatingMode::Ready))
+    );
+    tof.try_start_range_continuous_mode().unwrap();
+    assert_eq!(
+        tof.try_poll_range_mm_single_auto_scaling_blocking(),
+        Err(Error::InvalidMethod(OperatingMode::RangeContinuous))
+    );
+    assert_eq!(
+        tof.try_read_range_mm_auto_scaling_blocking(),
+        Ok(ScaledRangeMeasurement {
+            range_mm: 300,
+            scaling: 2
+        })
+    );
+    tof.try_stop_range_continuous_mode().unwrap();
+
+    sensor.set_range_mm(50);
+    assert_eq!(
+        tof.try_poll_range_mm_single_auto_scaling_blocking(),
+        Ok(ScaledRangeMeasurement {
+            range_mm: 50,
+            scaling: 1
+        })
+    );
+}
+
+#[test]
+fn read_range_auto_scaling_drops_sample_with_previous_scaling() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(300);
+    sensor.set_measurement_time_us(10_000);
+    let tof = VL6180X::new_with_delay(bus.i2c(), bus.delay()).unwrap();
+    let mut tof = tof.start_range_continuous_mode().unwrap();
+    let start_us = bus.now_us();
+
+    // The scaling is switched while the second sample is taken with the previous
+    // scaling, so the third sample is read.
+    let measurement = tof.read_range_mm_auto_scaling_blocking().unwrap();
+    assert_eq!(measurement.scaling, 2);
+    assert_eq!(measurement.range_mm, 300);
+    assert!(bus.now_us() - start_us >= 30_000);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_range_auto_scaling() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(400);
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        let measurement = tof.poll_range_mm_single_auto_scaling().await.unwrap();
+        assert_eq!(measurement.scaling, 2);
+
+        sensor.set_range_mm(100);
+        let mut tof = tof.start_range_continuous_mode().await.unwrap();
+        assert_eq!(
+            tof.read_range_mm_auto_scaling().await,
+            Ok(ScaledRangeMeasurement {
+                range_mm: 100,
+                scaling: 1
+            })
+        );
+    });
+}
@@ -1,7 +1,7 @@
 use core::convert::TryFrom;
-use int_enum::{IntEnum, IntEnumError};
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod register_tests;
 
 #[allow(non_camel_case_types)]
@@ -89,15 +89,6 @@ pub enum Register32Bit {
     RESULT__RANGE_REFERENCE_CONV_TIME = 0x080,    // 32-bit
 }
 
-pub enum SysModeGpio1Polarity {
-    ActiveLow = 0b00_0_0000_0,
-    ActiveHigh = 0b00_1_0000_0,
-}
-pub enum SysModeGpio1Select {
-    Off = 0b00_0_0000_0,
-    InterruptOutput = 0b00_0_1000_0,
-}
-
 /// Sets the range mode and triggers start/stop.
 ///
 /// Bit 1: sysrange__mode_select: Device Mode select
@@ -112,6 +103,7 @@ pub enum SysModeGpio1Select {
 ///
 /// This bit is auto-cleared in both modes of operation.
 /// Register: SYSRANGE__START
+#[allow(clippy::unusual_byte_groupings)]
 pub enum SysRangeStartCode {
     SingleStart = 0b000000_01,
     ContinuousStartOrStop = 0b000000_11,
@@ -119,6 +111,7 @@ pub enum SysRangeStartCode {
 
 /// int_clear_sig: Interrupt clear bits.
 /// Writing a 1 to each bit will clear the intended interrupt.
+#[allow(clippy::unusual_byte_groupings)]
 pub enum SysInterruptClearCode {
     Range = 0b00000_001,
     Ambient = 0b00000_010,
@@ -139,11 +132,25 @@ pub enum SysInterruptClearCode {
 ///
 /// This bit is auto-cleared in both modes of operation.
 /// Register: SYSALS__START
+#[allow(clippy::unusual_byte_groupings)]
 pub enum SysAmbientStartCode {
     SingleStart = 0b000000_01,
     ContinuousStartOrStop = 0b000000_11,
 }
 
+/// Bit 2: history_buffer_clear: Writing a 1 clears all history buffer registers.
+/// The bit is auto-cleared. Bits 1:0 select the history buffer mode, see
+/// [HistoryBufferMode](crate::config::HistoryBufferMode).
+/// Register: SYSTEM__HISTORY_CTRL
+pub enum SysHistoryCtrlCode {
+    Clear = 0b0000_0100,
+}
+
+/// Number of range measurements kept in the history buffer, 8-bit each.
+pub const RANGE_HISTORY_LEN: usize = 16;
+/// Number of ambient light measurements kept in the history buffer, 16-bit each.
+pub const AMBIENT_HISTORY_LEN: usize = 8;
+
 pub enum InterleavedModeEnableCode {
     Enable = 1,
     Disable = 0,
@@ -207,7 +214,7 @@ impl ResultInterruptStatusGpioCode {
 /// or section 6.2.37 RESULT__RANGE_STATUS
 // Bits 7:4 of what is returned from the register
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, IntEnum, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum RangeStatusErrorCode {
     /// Valid measurement
     NoError = 0b0000,
@@ -247,9 +254,26 @@ impl RangeStatusErrorCode {
 }
 
 impl TryFrom<u8> for RangeStatusErrorCode {
-    type Error = IntEnumError<Self>;
+    type Error = u8;
     fn try_from(code: u8) -> Result<Self, Self::Error> {
-        RangeStatusErrorCode::from_int(code >> 4)
+        use RangeStatusErrorCode::*;
+        match code >> 4 {
+            0b0000 => Ok(NoError),
+            0b0001 => Ok(VcselContinuityTest),
+            0b0010 => Ok(VcselWatchdogTest),
+            0b0011 => Ok(VcselWatchdog),
+            0b0100 => Ok(Pll1Lock),
+            0b0101 => Ok(Pll2Lock),
+            0b0110 => Ok(EarlyConvergenceEstimate),
+            0b0111 => Ok(MaxConvergence),
+            0b1000 => Ok(RangeIgnore),
+            0b1011 => Ok(MaxSignalToNoiseRatio),
+            0b1100 => Ok(RawRangingAlgoUnderflow),
+            0b1101 => Ok(RawRangingAlgoOverflow),
+            0b1110 => Ok(RangingAlgoUnderflow),
+            0b1111 => Ok(RangingAlgoOverflow),
+            _ => Err(code),
+        }
     }
 }
 
@@ -257,7 +281,7 @@ impl TryFrom<u8> for RangeStatusErrorCode {
 /// See VL6180X datasheet section 6.2.38 RESULT__ALS_STATUS
 // Bits 7:4 of what is returned from the register
 #[repr(u8)]
-#[derive(Debug, Copy, Clone, IntEnum, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum AmbientStatusErrorCode {
     /// Valid measurement
     NoError = 0b0000,
@@ -274,9 +298,15 @@ impl AmbientStatusErrorCode {
 }
 
 impl TryFrom<u8> for AmbientStatusErrorCode {
-    type Error = IntEnumError<Self>;
+    type Error = u8;
     fn try_from(code: u8) -> Result<Self, Self::Error> {
-        AmbientStatusErrorCode::from_int(code >> 4)
+        use AmbientStatusErrorCode::*;
+        match code >> 4 {
+            0b0000 => Ok(NoError),
+            0b0001 => Ok(Overflow),
+            0b0010 => Ok(Underflow),
+            _ => Err(code),
+        }
     }
 }
 // RANGE_SCALER values for 1x, 2x, 3x scaling - see STSW-IMG003 core/src/vl6180x_api.c (ScalerLookUP[])
@@ -284,3 +314,8 @@ pub const RANGE_SCALAR_CODE: [u16; 4] = [0, 253, 127, 84];
 /// See datasheet 2.10.6 for more details
 pub const AMBIENT_ANALOGUE_GAIN_CODE: [u8; 8] = [0x46, 0x45, 0x44, 0x43, 0x42, 0x41, 0x40, 0x47];
 pub const AMBIENT_ANALOGUE_GAIN_VALUE: [f32; 8] = [1.01, 1.28, 1.72, 2.60, 5.21, 10.32, 20.0, 40.0];
+/// SYSRANGE__RANGE_CHECK_ENABLES bits enabling the early convergence estimate,
+/// range ignore and signal to noise ratio checks
+pub const RANGE_CHECK_EARLY_CONVERGENCE_ESTIMATE: u8 = 0b0_0001;
+pub const RANGE_CHECK_RANGE_IGNORE: u8 = 0b0_0010;
+pub const RANGE_CHECK_SIGNAL_TO_NOISE_RATIO: u8 = 0b1_0000;
@@ -0,0 +1,169 @@
+use crate::{
+    config::DEFAULT_ADDRESS,
+    error::{Error, Error2, SensorArrayError},
+    mode::ReadyMode,
+    BusyWait, Config, WaitStrategy, VL6180X,
+};
+use embedded_hal::{delay::DelayNs, digital::OutputPin, i2c::I2c};
+
+#[cfg(test)]
+mod sensor_array_tests;
+
+/// Time the x shutdown pins are held low before the sensors are powered on again, so
+/// sensors that were running are fully reset.
+pub(crate) const X_SHUTDOWN_LOW_TIME_US: u32 = 1000;
+
+type ArrayError<E, PE> = SensorArrayError<Error2<E, PE>>;
+type ReadyArray<I2C, W, const N: usize> = [VL6180X<ReadyMode, I2C, W>; N];
+
+/// Several sensors sharing one I2C bus, each with its own x shutdown pin.
+///
+/// All sensors boot with the same I2C address, so they have to be brought up one at a
+/// time and moved to a unique address, following
+/// [ST application note AN4478](https://www.st.com/resource/en/application_note/an4478-using-multiple-vl6180xs-in-a-single-design-stmicroelectronics.pdf).
+/// The array keeps the x shutdown pins, as the addresses are lost when the sensors are
+/// powered off and the sequence has to be repeated with [`init`](SensorArray::init).
+#[derive(Debug)]
+pub struct SensorArray<P, const N: usize> {
+    sensors: [(P, u8, Config); N],
+}
+
+impl<P, PE, const N: usize> SensorArray<P, N>
+where
+    P: OutputPin<Error = PE>,
+{
+    /// Create a sensor array from `(x_shutdown_pin, target_address, config)` entries.
+    ///
+    /// The address of each `config` is ignored, the sensor is moved to `target_address`.
+    pub fn new(sensors: [(P, u8, Config); N]) -> Self {
+        Self { sensors }
+    }
+
+    /// Power off all sensors, then power them on one at a time, move each to its target
+    /// address and initialize it with its config.
+    ///
+    /// `new_i2c` is called once per sensor to get its handle to the shared bus, e.g. a
+    /// device from [embedded-hal-bus](https://crates.io/crates/embedded-hal-bus).
+    /// `delay` is used to hold the sensors in reset and to wait for each sensor to boot, see
+    /// [boot timeout](crate::Config::set_boot_timeout_us).
+    /// The returned drivers are in the same order as the entries.
+    /// Errors carry the index of the sensor that failed, sensors after it are left
+    /// powered off.
+    pub fn init<I2C, E, F, D>(
+        &mut self,
+        new_i2c: F,
+        delay: &mut D,
+    ) -> Result<ReadyArray<I2C, BusyWait, N>, ArrayError<E, PE>>
+    where
+        I2C: I2c<Error = E>,
+        F: FnMut() -> I2C,
+        D: DelayNs,
+    {
+        self.init_with_wait_strategy(new_i2c, BusyWait::default, delay)
+    }
+
+    /// Same as [`init`](SensorArray::init), with `new_wait` called once per sensor to get
+    /// the [wait strategy](crate::VL6180X::with_wait_strategy) of its driver.
+    pub fn init_with_wait_strategy<I2C, E, F, G, W, D>(
+        &mut self,
+        mut new_i2c: F,
+        mut new_wait: G,
+        delay: &mut D,
+    ) -> Result<ReadyArray<I2C, W, N>, ArrayError<E, PE>>
+    where
+        I2C: I2c<Error = E>,
+        F: FnMut() -> I2C,
+        G: FnMut() -> W,
+        W: WaitStrategy,
+        D: DelayNs,
+    {
+        self.check_addresses()?;
+        self.power_off().map_err(|e| SensorArrayError {
+            index: e.index,
+            error: Error2::GpioPinError(e.error),
+        })?;
+        delay.delay_us(X_SHUTDOWN_LOW_TIME_US);
+
+        let mut chips: [Option<VL6180X<ReadyMode, I2C, W>>; N] = [(); N].map(|_| None);
+        for (index, (x_shutdown_pin, address, config)) in self.sensors.iter_mut().enumerate() {
+            let chip = boot_sensor(
+                new_i2c(),
+                new_wait(),
+                x_shutdown_pin,
+                delay,
+                *address,
+                config,
+            )
+            .map_err(|error| SensorArrayError { index, error })?;
+            chips[index] = Some(chip);
+        }
+        Ok(chips.map(|chip| chip.expect("all sensors are initialized")))
+    }
+
+    /// Power off all sensors.
+    pub fn power_off(&mut self) -> Result<(), SensorArrayError<PE>> {
+        for (index, (x_shutdown_pin, _, _)) in self.sensors.iter_mut().enumerate() {
+            x_shutdown_pin
+                .set_low()
+                .map_err(|error| SensorArrayError { index, error })?;
+        }
+        Ok(())
+    }
+
+    /// Release the `(x_shutdown_pin, target_address, config)` entries.
+    pub fn release(self) -> [(P, u8, Config); N] {
+        self.sensors
+    }
+
+    /// Target addresses must be valid and unique. Only the last sensor may keep the
+    /// default address, otherwise it would collide with the sensors booted after it.
+    fn check_addresses<E>(&self) -> Result<(), ArrayError<E, PE>> {
+        for (index, (_, address, _)) in self.sensors.iter().enumerate() {
+            let invalid = !(0x08..=0x77).contains(address)
+                || (*address == DEFAULT_ADDRESS && index != N - 1)
+                || self.sensors[..index].iter().any(|(_, a, _)| a == address);
+            if invalid {
+                return Err(SensorArrayError {
+                    index,
+                    error: Error2::DriverError(Error::InvalidAddress(*address)),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn boot_sensor<I2C, E, W, P, PE, D>(
+    i2c: I2C,
+    wait: W,
+    x_shutdown_pin: &mut P,
+    delay: &mut D,
+    address: u8,
+    config: &Config,
+) -> Result<VL6180X<ReadyMode, I2C, W>, Error2<E, PE>>
+where
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
+    P: OutputPin<Error = PE>,
+    D: DelayNs,
+{
+    let mut config = *config;
+    config.address = DEFAULT_ADDRESS;
+    let mut chip = VL6180X {
+        mode: ReadyMode,
+        com: i2c,
+        config,
+        wait,
+    };
+
+    x_shutdown_pin.set_high().map_err(Error2::GpioPinError)?;
+    // Waits the boot time before the first poll of SYSTEM__FRESH_OUT_OF_RESET
+    chip.wait_device_booted(delay)?;
+    let chip_id = chip.read_model_id_direct()?;
+    if chip_id != 0xB4 {
+        return Err(Error2::DriverError(Error::InvalidDevice(chip_id)));
+    }
+    chip.change_i2c_address_direct(address)?;
+    chip.init_hardware()?;
+    Ok(chip)
+}
@@ -0,0 +1,158 @@
+use super::*;
+use crate::{
+    register::Register8Bit,
+    sim::{SimBus, SimSensor},
+};
+
+fn three_sensor_bus() -> (SimBus, [SimSensor; 3]) {
+    let bus = SimBus::new();
+    let sensors = [bus.add_sensor(), bus.add_sensor(), bus.add_sensor()];
+    for (i, sensor) in sensors.iter().enumerate() {
+        sensor.set_range_mm(10 * (i as u16 + 1));
+    }
+    (bus, sensors)
+}
+
+#[test]
+fn init_assigns_addresses() {
+    let (bus, sensors) = three_sensor_bus();
+    let mut config = Config::new();
+    config.set_i2c_address(0x50);
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x30, config),
+        (sensors[1].x_shutdown_pin(), 0x31, config),
+        (sensors[2].x_shutdown_pin(), 0x29, config),
+    ]);
+    let mut tofs = array.init(|| bus.i2c(), &mut bus.delay()).unwrap();
+
+    assert_eq!(sensors[0].address(), 0x30);
+    assert_eq!(sensors[1].address(), 0x31);
+    assert_eq!(sensors[2].address(), 0x29);
+    for (i, tof) in tofs.iter_mut().enumerate() {
+        assert_eq!(tof.poll_range_mm_single_blocking(), Ok(10 * (i as u16 + 1)));
+    }
+}
+
+#[test]
+fn init_waits_reset_and_boot_times() {
+    let (bus, sensors) = three_sensor_bus();
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x30, Config::new()),
+        (sensors[1].x_shutdown_pin(), 0x31, Config::new()),
+        (sensors[2].x_shutdown_pin(), 0x32, Config::new()),
+    ]);
+    array.init(|| bus.i2c(), &mut bus.delay()).unwrap();
+    assert_eq!(
+        bus.now_us(),
+        (X_SHUTDOWN_LOW_TIME_US + 3 * crate::device_status::BOOT_TIME_US) as u64
+    );
+}
+
+#[test]
+fn init_applies_config_per_sensor() {
+    let (bus, sensors) = three_sensor_bus();
+    let mut scaled = Config::new();
+    scaled.set_range_result_scaler(2).unwrap();
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x30, Config::new()),
+        (sensors[1].x_shutdown_pin(), 0x31, scaled),
+        (sensors[2].x_shutdown_pin(), 0x32, Config::new()),
+    ]);
+    array.init(|| bus.i2c(), &mut bus.delay()).unwrap();
+    assert_eq!(
+        sensors[0].register(Register8Bit::SYSRANGE__MAX_CONVERGENCE_TIME as u16),
+        49
+    );
+    assert_eq!(
+        sensors[1].register_16bit(crate::register::Register16Bit::RANGE_SCALER as u16),
+        crate::register::RANGE_SCALAR_CODE[2]
+    );
+}
+
+#[test]
+fn init_again_after_power_off() {
+    let (bus, sensors) = three_sensor_bus();
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x30, Config::new()),
+        (sensors[1].x_shutdown_pin(), 0x31, Config::new()),
+        (sensors[2].x_shutdown_pin(), 0x32, Config::new()),
+    ]);
+    array.init(|| bus.i2c(), &mut bus.delay()).unwrap();
+    array.power_off().unwrap();
+    assert!(sensors.iter().all(|s| !s.is_powered()));
+
+    let mut tofs = array.init(|| bus.i2c(), &mut bus.delay()).unwrap();
+    assert_eq!(sensors[2].address(), 0x32);
+    assert_eq!(tofs[2].poll_range_mm_single_blocking(), Ok(30));
+}
+
+#[test]
+fn init_rejects_duplicate_address() {
+    let (bus, sensors) = three_sensor_bus();
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x30, Config::new()),
+        (sensors[1].x_shutdown_pin(), 0x31, Config::new()),
+        (sensors[2].x_shutdown_pin(), 0x30, Config::new()),
+    ]);
+    assert_eq!(
+        array.init(|| bus.i2c(), &mut bus.delay()).err().unwrap(),
+        SensorArrayError {
+            index: 2,
+            error: Error2::DriverError(Error::InvalidAddress(0x30)),
+        }
+    );
+    // Nothing is touched when the addresses are invalid
+    assert!(sensors.iter().all(|s| s.is_powered()));
+}
+
+#[test]
+fn init_rejects_default_address_before_last() {
+    let (bus, sensors) = three_sensor_bus();
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x29, Config::new()),
+        (sensors[1].x_shutdown_pin(), 0x31, Config::new()),
+        (sensors[2].x_shutdown_pin(), 0x32, Config::new()),
+    ]);
+    assert_eq!(
+        array.init(|| bus.i2c(), &mut bus.delay()).err().unwrap(),
+        SensorArrayError {
+            index: 0,
+            error: Error2::DriverError(Error::InvalidAddress(0x29)),
+        }
+    );
+}
+
+#[test]
+fn init_rejects_invalid_address() {
+    let (bus, sensors) = three_sensor_bus();
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x30, Config::new()),
+        (sensors[1].x_shutdown_pin(), 0x31, Config::new()),
+        (sensors[2].x_shutdown_pin(), 0x78, Config::new()),
+    ]);
+    assert_eq!(
+        array.init(|| bus.i2c(), &mut bus.delay()).err().unwrap(),
+        SensorArrayError {
+            index: 2,
+            error: Error2::DriverError(Error::InvalidAddress(0x78)),
+        }
+    );
+}
+
+#[test]
+fn init_identifies_sensor_that_fails_to_boot() {
+    let (bus, sensors) = three_sensor_bus();
+    sensors[1].set_boot_time_us(100_000);
+    let mut array = SensorArray::new([
+        (sensors[0].x_shutdown_pin(), 0x30, Config::new()),
+        (sensors[1].x_shutdown_pin(), 0x31, Config::new()),
+        (sensors[2].x_shutdown_pin(), 0x32, Config::new()),
+    ]);
+    let error = array.init(|| bus.i2c(), &mut bus.delay()).err().unwrap();
+    assert_eq!(error.index, 1);
+    assert!(matches!(
+        error.error,
+        Error2::DriverError(Error::BootTimeout(Some(_)))
+    ));
+    assert!(!sensors[2].is_powered());
+}
@@ -0,0 +1,950 @@
+//! Register level simulation of the VL6180X for host testing.
+//!
+//! Enabled with the `sim` feature. A [SimBus] owns one or more simulated sensors
+//! and hands out the I2C bus, delay and pins needed by the driver, all sharing
+//! the same simulated clock:
+//!
+//! ```
+//! # use vl6180x::sim::SimBus;
+//! let (bus, sensor) = SimBus::with_sensor();
+//! sensor.set_range_mm(120);
+//!
+//! let mut tof = vl6180x::VL6180X::new(bus.i2c()).unwrap();
+//! assert_eq!(tof.poll_range_mm_single_blocking(), Ok(120));
+//! ```
+//!
+//! The simulation models the register file, the boot sequence
+//! (`SYSTEM__FRESH_OUT_OF_RESET`), the `SYSRANGE__START` and `SYSALS__START`
+//! single shot and continuous state machines, interleaved mode, the interrupt
+//! status and clear registers, the interrupt thresholds and the grouped parameter hold,
+//! the GPIO0 and GPIO1 interrupt outputs, cover window crosstalk and its compensation,
+//! the early convergence estimate and range ignore checks, the ambient light analogue
+//! gain and integration period, the history buffer and the result registers. Private
+//! tuning registers are stored but have no effect.
+//!
+//! Time only advances through [SimDelay], or by [`set_transaction_time_us`](SimBus::set_transaction_time_us)
+//! for each bus transaction, so measurements complete instantly unless
+//! [`set_measurement_time_us`](SimSensor::set_measurement_time_us) is set.
+extern crate std;
+
+use crate::register::{
+    AmbientStatusErrorCode, RangeStatusErrorCode, Register16Bit, Register32Bit, Register8Bit,
+    SysAmbientStartCode, SysRangeStartCode, AMBIENT_ANALOGUE_GAIN_CODE,
+    AMBIENT_ANALOGUE_GAIN_VALUE, RANGE_CHECK_EARLY_CONVERGENCE_ESTIMATE, RANGE_CHECK_RANGE_IGNORE,
+    RANGE_SCALAR_CODE,
+};
+use core::{cell::RefCell, convert::Infallible};
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{self, InputPin, OutputPin},
+    i2c::{self, ErrorKind, NoAcknowledgeSource, Operation},
+};
+use std::{rc::Rc, vec, vec::Vec};
+
+#[cfg(test)]
+mod sim_tests;
+
+/// Default 7 bit I2C address of the sensor.
+const DEFAULT_ADDRESS: u8 = 0x29;
+/// Size of the simulated register file.
+const REGISTER_COUNT: usize = 0x300;
+
+const RANGE_INTERRUPT_MASK: u8 = 0b00_000_111;
+const AMBIENT_INTERRUPT_MASK: u8 = 0b00_111_000;
+const ERROR_INTERRUPT_MASK: u8 = 0b11_000_000;
+
+const HISTORY_ENABLE: u8 = 0b001;
+const HISTORY_AMBIENT: u8 = 0b010;
+const HISTORY_CLEAR: u8 = 0b100;
+/// Size of the history buffer, RESULT__HISTORY_BUFFER_0 to RESULT__HISTORY_BUFFER_7.
+const HISTORY_LEN: u16 = 16;
+
+/// Reference array return rate reported with every range measurement, 5 Mcps.
+const REFERENCE_RATE: u16 = 5 << 7;
+/// Convergence time reported for both arrays with every range measurement.
+const CONVERGENCE_TIME: u32 = 1000;
+/// Lux per ALS count at unity gain and 1 ms integration, the datasheet 0.32 lux at 100 ms.
+const LUX_PER_COUNT: f32 = 32.0;
+
+#[derive(Debug, Default)]
+struct World {
+    now_us: u64,
+    transaction_time_us: u64,
+    sensors: Vec<Sensor>,
+}
+
+impl World {
+    fn advance(&mut self, us: u64) {
+        self.now_us += us;
+        let now = self.now_us;
+        for sensor in self.sensors.iter_mut() {
+            sensor.update(now);
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Measurement {
+    continuous: bool,
+    ready_at_us: Option<u64>,
+    /// Settings the measurement in progress was started with, the sensor keeps using
+    /// them when they are changed during the measurement.
+    settings: Option<Settings>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+    range_scaling: u16,
+    ambient_gain: f32,
+    integration_ms: f32,
+}
+
+#[derive(Debug)]
+struct Sensor {
+    powered: bool,
+    booted_at_us: u64,
+    boot_time_us: u64,
+    registers: Vec<u8>,
+    pointer: u16,
+    /// Writes to grouped registers made during a grouped parameter hold.
+    held_writes: Vec<(u16, u8)>,
+
+    range: Measurement,
+    ambient: Measurement,
+
+    // Scene
+    measurement_time_us: u64,
+    range_mm: u16,
+    range_error_mm: i8,
+    signal_rate: u16,
+    crosstalk_rate: u16,
+    range_status: Option<RangeStatusErrorCode>,
+    ambient_counts: u16,
+    ambient_lux: Option<f32>,
+    ambient_status: Option<AmbientStatusErrorCode>,
+}
+
+/// Registers whose updates are deferred by SYSTEM__GROUPED_PARAMETER_HOLD.
+fn is_grouped(reg: u16) -> bool {
+    use Register16Bit::*;
+    use Register8Bit::*;
+
+    let als_high = SYSALS__THRESH_HIGH as u16;
+    let als_low = SYSALS__THRESH_LOW as u16;
+    let als_integration = SYSALS__INTEGRATION_PERIOD as u16;
+    reg == SYSTEM__INTERRUPT_CONFIG_GPIO as u16
+        || reg == SYSALS__ANALOGUE_GAIN as u16
+        || reg == als_integration
+        || reg == als_integration + 1
+        || reg == SYSRANGE__THRESH_HIGH as u16
+        || reg == SYSRANGE__THRESH_LOW as u16
+        || reg == als_high
+        || reg == als_high + 1
+        || reg == als_low
+        || reg == als_low + 1
+}
+
+impl Sensor {
+    fn new() -> Self {
+        let mut sensor = Sensor {
+            powered: true,
+            booted_at_us: 0,
+            boot_time_us: 0,
+            registers: vec![0; REGISTER_COUNT],
+            pointer: 0,
+            held_writes: Vec::new(),
+            range: Measurement::default(),
+            ambient: Measurement::default(),
+            measurement_time_us: 0,
+            range_mm: 100,
+            range_error_mm: 0,
+            signal_rate: 10 << 7,
+            crosstalk_rate: 0,
+            range_status: None,
+            ambient_counts: 0,
+            ambient_lux: None,
+            ambient_status: None,
+        };
+        sensor.reset_registers();
+        sensor
+    }
+
+    /// Register values after reset, see VL6180X datasheet section 6.
+    fn reset_registers(&mut self) {
+        use Register16Bit::*;
+        use Register8Bit::*;
+
+        for reg in self.registers.iter_mut() {
+            *reg = 0;
+        }
+        self.held_writes.clear();
+        self.set8(IDENTIFICATION__MODEL_ID as u16, 0xB4);
+        self.set8(IDENTIFICATION__MODEL_REV_MAJOR as u16, 0x01);
+        self.set8(IDENTIFICATION__MODEL_REV_MINOR as u16, 0x03);
+        self.set8(IDENTIFICATION__MODULE_REV_MAJOR as u16, 0x01);
+        self.set8(IDENTIFICATION__MODULE_REV_MINOR as u16, 0x02);
+        // Manufactured 2022-06-15, phase 2, at 13:45:10
+        self.set8(IDENTIFICATION__DATE_HI as u16, 0x26);
+        self.set8(IDENTIFICATION__DATE_LO as u16, (15 << 3) | 2);
+        self.set16(IDENTIFICATION__TIME as u16, 49510 / 2);
+        self.set8(SYSTEM__MODE_GPIO0 as u16, 0x60);
+        self.set8(SYSTEM__MODE_GPIO1 as u16, 0x20);
+        self.set8(SYSTEM__FRESH_OUT_OF_RESET as u16, 0x01);
+        self.set8(SYSRANGE__THRESH_HIGH as u16, 0xFF);
+        self.set8(SYSRANGE__INTERMEASUREMENT_PERIOD as u16, 0xFF);
+        self.set8(SYSRANGE__MAX_CONVERGENCE_TIME as u16, 0x31);
+        self.set8(SYSRANGE__CROSSTALK_VALID_HEIGHT as u16, 0x14);
+        self.set8(SYSRANGE__MAX_AMBIENT_LEVEL_MULT as u16, 0xA0);
+        self.set8(SYSRANGE__RANGE_CHECK_ENABLES as u16, 0x11);
+        self.set8(SYSALS__INTERMEASUREMENT_PERIOD as u16, 0xFF);
+        self.set8(SYSALS__ANALOGUE_GAIN as u16, 0x06);
+        self.set8(READOUT__AVERAGING_SAMPLE_PERIOD as u16, 0x30);
+        self.set8(FIRMWARE__RESULT_SCALER as u16, 0x01);
+        self.set8(I2C_SLAVE__DEVICE_ADDRESS as u16, DEFAULT_ADDRESS);
+        self.set16(SYSRANGE__EARLY_CONVERGENCE_ESTIMATE as u16, 0x0000);
+        self.set16(SYSALS__THRESH_HIGH as u16, 0xFFFF);
+        self.set16(RANGE_SCALER as u16, RANGE_SCALAR_CODE[1]);
+    }
+
+    fn get8(&self, reg: u16) -> u8 {
+        self.registers.get(reg as usize).copied().unwrap_or(0)
+    }
+
+    fn set8(&mut self, reg: u16, value: u8) {
+        if let Some(r) = self.registers.get_mut(reg as usize) {
+            *r = value;
+        }
+    }
+
+    fn get16(&self, reg: u16) -> u16 {
+        u16::from_be_bytes([self.get8(reg), self.get8(reg + 1)])
+    }
+
+    fn set16(&mut self, reg: u16, value: u16) {
+        let bytes = value.to_be_bytes();
+        self.set8(reg, bytes[0]);
+        self.set8(reg + 1, bytes[1]);
+    }
+
+    fn set32(&mut self, reg: u16, value: u32) {
+        let bytes = value.to_be_bytes();
+        for (i, byte) in bytes.iter().enumerate() {
+            self.set8(reg + i as u16, *byte);
+        }
+    }
+
+    fn address(&self) -> u8 {
+        self.get8(Register8Bit::I2C_SLAVE__DEVICE_ADDRESS as u16)
+    }
+
+    fn responds(&self, address: u8, now_us: u64) -> bool {
+        self.powered && now_us >= self.booted_at_us && self.address() == address
+    }
+
+    fn power(&mut self, on: bool, now_us: u64) {
+        if on && !self.powered {
+            self.reset_registers();
+            self.range = Measurement::default();
+            self.ambient = Measurement::default();
+            self.booted_at_us = now_us + self.boot_time_us;
+        }
+        self.powered = on;
+    }
+
+    fn range_scaling(&self) -> u16 {
+        let code = self.get16(Register16Bit::RANGE_SCALER as u16);
+        match RANGE_SCALAR_CODE.iter().position(|c| *c == code) {
+            Some(scaling) if scaling > 0 => scaling as u16,
+            _ => 1,
+        }
+    }
+
+    fn interleaved(&self) -> bool {
+        self.get8(Register8Bit::INTERLEAVED_MODE__ENABLE as u16) & 0x01 != 0
+    }
+
+    fn interrupt_status(&self) -> u8 {
+        self.get8(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO as u16)
+    }
+
+    fn set_interrupt_status(&mut self, mask: u8, bits: u8) {
+        let status = (self.interrupt_status() & !mask) | (bits & mask);
+        self.set8(Register8Bit::RESULT__INTERRUPT_STATUS_GPIO as u16, status);
+    }
+
+    /// Completes measurements that are due and schedules the next continuous sample.
+    fn update(&mut self, now_us: u64) {
+        if !self.powered {
+            return;
+        }
+        if let Some(ready_at) = self.ambient.ready_at_us {
+            // A continuous sample is only produced once the host consumed the last one
+            let consumed = self.interrupt_status() & AMBIENT_INTERRUPT_MASK == 0;
+            if now_us >= ready_at && (!self.ambient.continuous || consumed) {
+                self.complete_ambient();
+                if self.interleaved() && self.ambient.continuous {
+                    self.complete_range();
+                }
+                self.ambient.ready_at_us = if self.ambient.continuous {
+                    Some(now_us + self.measurement_time_us)
+                } else {
+                    None
+                };
+                self.ambient.settings = Some(self.settings());
+            }
+        }
+        if let Some(ready_at) = self.range.ready_at_us {
+            let consumed = self.interrupt_status() & RANGE_INTERRUPT_MASK == 0;
+            if now_us >= ready_at && (!self.range.continuous || consumed) {
+                self.complete_range();
+                self.range.ready_at_us = if self.range.continuous {
+                    Some(now_us + self.measurement_time_us)
+                } else {
+                    None
+                };
+                self.range.settings = Some(self.settings());
+            }
+        }
+    }
+
+    /// Range seen by the sensor. Crosstalk returns from the cover window at close to
+    /// 0mm, pulling the range short by its share of the return rate, minus what is compensated.
+    fn measured_range_mm(&self) -> u16 {
+        let compensation = self.get16(Register16Bit::SYSRANGE__CROSSTALK_COMPENSATION_RATE as u16);
+        let crosstalk = self.crosstalk_rate.saturating_sub(compensation) as u32;
+        let signal = self.signal_rate as u32;
+        if signal + crosstalk == 0 {
+            return self.range_mm;
+        }
+        (self.range_mm as u32 * signal / (signal + crosstalk)) as u16
+    }
+
+    fn complete_range(&mut self) {
+        use Register16Bit::*;
+        use Register8Bit::*;
+
+        let scaling = self
+            .range
+            .settings
+            .map_or_else(|| self.range_scaling(), |settings| settings.range_scaling);
+        let offset = self.get8(SYSRANGE__PART_TO_PART_RANGE_OFFSET as u16) as i8 as i32;
+        let measured = self.measured_range_mm() as i32 + self.range_error_mm as i32;
+        let raw = measured.max(0) / scaling as i32;
+        let value = raw + offset;
+
+        let return_rate = self.signal_rate.saturating_add(self.crosstalk_rate);
+        let checks = self.get8(SYSRANGE__RANGE_CHECK_ENABLES as u16);
+        // The early convergence estimate compares the return signal count 0.5 ms into
+        // the measurement against SYSRANGE__EARLY_CONVERGENCE_ESTIMATE
+        let early_convergence_estimate = checks & RANGE_CHECK_EARLY_CONVERGENCE_ESTIMATE != 0
+            && (return_rate as u32 * 500) >> 7
+                < self.get16(SYSRANGE__EARLY_CONVERGENCE_ESTIMATE as u16) as u32;
+        // Range ignore rejects close targets with a low return signal rate, see
+        // datasheet SYSRANGE__RANGE_IGNORE_VALID_HEIGHT
+        let range_ignore = checks & RANGE_CHECK_RANGE_IGNORE != 0
+            && value < self.get8(SYSRANGE__RANGE_IGNORE_VALID_HEIGHT as u16) as i32
+            && return_rate < self.get16(SYSRANGE__RANGE_IGNORE_THRESHOLD as u16);
+
+        let status = match self.range_status {
+            Some(status) => status,
+            None if early_convergence_estimate => RangeStatusErrorCode::EarlyConvergenceEstimate,
+            None if range_ignore => RangeStatusErrorCode::RangeIgnore,
+            None if raw > 0xFF || value > 0xFF => RangeStatusErrorCode::RangingAlgoOverflow,
+            None if value < 0 => RangeStatusErrorCode::RangingAlgoUnderflow,
+            None => RangeStatusErrorCode::NoError,
+        };
+        let value = value.clamp(0, 0xFF) as u8;
+
+        self.set8(RESULT__RANGE_RAW as u16, raw.clamp(0, 0xFF) as u8);
+        self.set16(RESULT__RANGE_RETURN_RATE as u16, return_rate);
+        self.set16(RESULT__RANGE_REFERENCE_RATE as u16, REFERENCE_RATE);
+        self.set_range_counts(return_rate);
+        self.set8(RESULT__RANGE_VAL as u16, value);
+        self.push_history(false, &[value]);
+        self.set8(RESULT__RANGE_STATUS as u16, ((status as u8) << 4) | 0x01);
+
+        let low = self.get8(SYSRANGE__THRESH_LOW as u16);
+        let high = self.get8(SYSRANGE__THRESH_HIGH as u16);
+        let mode = self.get8(SYSTEM__INTERRUPT_CONFIG_GPIO as u16) & RANGE_INTERRUPT_MASK;
+        let event = threshold_event(mode, value < low, value > high);
+        self.set_interrupt_status(RANGE_INTERRUPT_MASK, event);
+    }
+
+    /// Signal counts are the rates integrated over the convergence time,
+    /// both arrays see the scene ambient light.
+    fn set_range_counts(&mut self, return_rate: u16) {
+        use Register32Bit::*;
+
+        let ambient = self.ambient_counts as u32;
+        self.set32(
+            RESULT__RANGE_RETURN_SIGNAL_COUNT as u16,
+            (return_rate as u32 * CONVERGENCE_TIME) >> 7,
+        );
+        self.set32(
+            RESULT__RANGE_REFERENCE_SIGNAL_COUNT as u16,
+            (REFERENCE_RATE as u32 * CONVERGENCE_TIME) >> 7,
+        );
+        self.set32(RESULT__RANGE_RETURN_AMB_COUNT as u16, ambient);
+        self.set32(RESULT__RANGE_REFERENCE_AMB_COUNT as u16, ambient);
+        self.set32(RESULT__RANGE_RETURN_CONV_TIME as u16, CONVERGENCE_TIME);
+        self.set32(RESULT__RANGE_REFERENCE_CONV_TIME as u16, CONVERGENCE_TIME);
+    }
+
+    /// Pushes `bytes` in front of the history buffer if it is enabled for range
+    /// or ambient light, dropping the oldest bytes.
+    fn push_history(&mut self, ambient: bool, bytes: &[u8]) {
+        let ctrl = self.get8(Register8Bit::SYSTEM__HISTORY_CTRL as u16);
+        if ctrl & HISTORY_ENABLE == 0 || (ctrl & HISTORY_AMBIENT != 0) != ambient {
+            return;
+        }
+        let start = Register16Bit::RESULT__HISTORY_BUFFER_0 as u16;
+        let shift = bytes.len() as u16;
+        for reg in (start + shift..start + HISTORY_LEN).rev() {
+            let value = self.get8(reg - shift);
+            self.set8(reg, value);
+        }
+        for (i, byte) in bytes.iter().enumerate() {
+            self.set8(start + i as u16, *byte);
+        }
+    }
+
+    fn complete_ambient(&mut self) {
+        use Register16Bit::*;
+        use Register8Bit::*;
+
+        let settings = self.ambient.settings.unwrap_or_else(|| self.settings());
+        let (value, overflow) = match self.ambient_lux {
+            Some(lux) => {
+                let counts = lux / LUX_PER_COUNT * settings.ambient_gain * settings.integration_ms;
+                (counts.min(0xFFFF as f32) as u16, counts > 0xFFFF as f32)
+            }
+            None => (self.ambient_counts, false),
+        };
+        let status = match self.ambient_status {
+            Some(status) => status,
+            None if overflow => AmbientStatusErrorCode::Overflow,
+            None => AmbientStatusErrorCode::NoError,
+        };
+        self.set16(RESULT__ALS_VAL as u16, value);
+        self.push_history(true, &value.to_be_bytes());
+        self.set8(RESULT__ALS_STATUS as u16, ((status as u8) << 4) | 0x01);
+
+        let low = self.get16(SYSALS__THRESH_LOW as u16);
+        let high = self.get16(SYSALS__THRESH_HIGH as u16);
+        let mode = (self.get8(SYSTEM__INTERRUPT_CONFIG_GPIO as u16) & AMBIENT_INTERRUPT_MASK) >> 3;
+        let event = threshold_event(mode, value < low, value > high);
+        self.set_interrupt_status(AMBIENT_INTERRUPT_MASK, event << 3);
+    }
+
+    /// Analogue gain selected by SYSALS__ANALOGUE_GAIN.
+    fn ambient_gain(&self) -> f32 {
+        let code = self.get8(Register8Bit::SYSALS__ANALOGUE_GAIN as u16);
+        AMBIENT_ANALOGUE_GAIN_CODE
+            .iter()
+            .position(|&c| c == code)
+            .map_or(1.0, |level| AMBIENT_ANALOGUE_GAIN_VALUE[level])
+    }
+
+    fn integration_ms(&self) -> f32 {
+        self.get16(Register16Bit::SYSALS__INTEGRATION_PERIOD as u16) as f32 + 1.0
+    }
+
+    /// Current settings, which a measurement started now is taken with.
+    fn settings(&self) -> Settings {
+        Settings {
+            range_scaling: self.range_scaling(),
+            ambient_gain: self.ambient_gain(),
+            integration_ms: self.integration_ms(),
+        }
+    }
+
+    fn read(&mut self) -> u8 {
+        let value = self.get8(self.pointer);
+        self.pointer = self.pointer.wrapping_add(1);
+        value
+    }
+
+    fn write(&mut self, value: u8, now_us: u64) {
+        use Register8Bit::*;
+
+        let reg = self.pointer;
+        self.pointer = self.pointer.wrapping_add(1);
+
+        if reg == SYSRANGE__START as u16 {
+            self.start(value, now_us, true);
+        } else if reg == SYSALS__START as u16 {
+            self.start(value, now_us, false);
+        } else if reg == SYSTEM__INTERRUPT_CLEAR as u16 {
+            let mut mask = 0;
+            if value & 0b001 != 0 {
+                mask |= RANGE_INTERRUPT_MASK;
+            }
+            if value & 0b010 != 0 {
+                mask |= AMBIENT_INTERRUPT_MASK;
+            }
+            if value & 0b100 != 0 {
+                mask |= ERROR_INTERRUPT_MASK;
+            }
+            self.set_interrupt_status(mask, 0);
+        } else if reg == SYSRANGE__VHV_RECALIBRATE as u16 {
+            // Recalibration completes immediately and the bit auto-clears
+        } else if reg == SYSTEM__HISTORY_CTRL as u16 {
+            if value & HISTORY_CLEAR != 0 {
+                let start = Register16Bit::RESULT__HISTORY_BUFFER_0 as u16;
+                for reg in start..start + HISTORY_LEN {
+                    self.set8(reg, 0);
+                }
+            }
+            self.set8(reg, value & !HISTORY_CLEAR);
+        } else if reg == I2C_SLAVE__DEVICE_ADDRESS as u16 {
+            self.set8(reg, value & 0x7F);
+        } else if reg == SYSTEM__GROUPED_PARAMETER_HOLD as u16 {
+            self.set8(reg, value & 0x01);
+            if value & 0x01 == 0 {
+                for (reg, value) in core::mem::take(&mut self.held_writes) {
+                    self.set8(reg, value);
+                }
+            }
+        } else if self.get8(SYSTEM__GROUPED_PARAMETER_HOLD as u16) != 0 && is_grouped(reg) {
+            // The device keeps using the previous values until the hold is released
+            self.held_writes.push((reg, value));
+        } else {
+            self.set8(reg, value);
+        }
+    }
+
+    fn start(&mut self, code: u8, now_us: u64, range: bool) {
+        let single = if range {
+            SysRangeStartCode::SingleStart as u8
+        } else {
+            SysAmbientStartCode::SingleStart as u8
+        };
+        let continuous = if range {
+            SysRangeStartCode::ContinuousStartOrStop as u8
+        } else {
+            SysAmbientStartCode::ContinuousStartOrStop as u8
+        };
+        let ready_at = Some(now_us + self.measurement_time_us);
+        let settings = Some(self.settings());
+        let measurement = if range {
+            &mut self.range
+        } else {
+            &mut self.ambient
+        };
+
+        if code == continuous {
+            if measurement.continuous {
+                *measurement = Measurement::default();
+            } else {
+                measurement.continuous = true;
+                measurement.ready_at_us = ready_at;
+                measurement.settings = settings;
+            }
+        } else if code == single && !measurement.continuous {
+            measurement.ready_at_us = ready_at;
+            measurement.settings = settings;
+        }
+    }
+
+    /// Output level of GPIO0 or GPIO1, see datasheet section 6.2.2 SYSTEM__MODE_GPIO0 and
+    /// 6.2.3 SYSTEM__MODE_GPIO1. A pin that is not an interrupt output reads as not asserted.
+    fn gpio_is_high(&self, gpio: u8) -> bool {
+        let mode_register = match gpio {
+            0 => Register8Bit::SYSTEM__MODE_GPIO0,
+            _ => Register8Bit::SYSTEM__MODE_GPIO1,
+        };
+        let mode = self.get8(mode_register as u16);
+        let active_high = mode & 0b0010_0000 != 0;
+        let interrupt_output = (mode >> 1) & 0b1111 == 0b1000;
+        let asserted = self.powered
+            && interrupt_output
+            && self.interrupt_status() & (RANGE_INTERRUPT_MASK | AMBIENT_INTERRUPT_MASK) != 0;
+        asserted == active_high
+    }
+}
+
+/// Interrupt event bits for the 3-bit interrupt mode `mode`.
+fn threshold_event(mode: u8, below_low: bool, above_high: bool) -> u8 {
+    match mode {
+        0b001 if below_low => 0b001,
+        0b010 if above_high => 0b010,
+        0b011 if below_low || above_high => 0b011,
+        0b100 => 0b100,
+        _ => 0,
+    }
+}
+
+/// A simulated I2C bus with any number of simulated VL6180X sensors on it.
+///
+/// Cloning the bus returns a new handle to the same simulation.
+#[derive(Debug, Clone, Default)]
+pub struct SimBus {
+    world: Rc<RefCell<World>>,
+}
+
+impl SimBus {
+    /// Create an empty bus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a bus with a single powered on sensor at the default address.
+    pub fn with_sensor() -> (Self, SimSensor) {
+        let bus = Self::new();
+        let sensor = bus.add_sensor();
+        (bus, sensor)
+    }
+
+    /// Add a powered on sensor at the default address to the bus.
+    pub fn add_sensor(&self) -> SimSensor {
+        let mut world = self.world.borrow_mut();
+        world.sensors.push(Sensor::new());
+        SimSensor {
+            world: self.world.clone(),
+            index: world.sensors.len() - 1,
+        }
+    }
+
+    /// I2C bus implementing [embedded_hal::i2c::I2c], to be handed to the driver.
+    pub fn i2c(&self) -> SimI2c {
+        SimI2c {
+            world: self.world.clone(),
+        }
+    }
+
+    /// Delay implementing [embedded_hal::delay::DelayNs], which advances the simulated clock.
+    pub fn delay(&self) -> SimDelay {
+        SimDelay {
+            world: self.world.clone(),
+        }
+    }
+
+    /// Current time of the simulated clock.
+    pub fn now_us(&self) -> u64 {
+        self.world.borrow().now_us
+    }
+
+    /// Advance the simulated clock.
+    pub fn advance_us(&self, us: u64) {
+        self.world.borrow_mut().advance(us);
+    }
+
+    /// Time the simulated clock advances for each I2C transaction. Default = 0
+    pub fn set_transaction_time_us(&self, us: u64) {
+        self.world.borrow_mut().transaction_time_us = us;
+    }
+}
+
+/// Handle to a single simulated sensor on a [SimBus].
+#[derive(Debug, Clone)]
+pub struct SimSensor {
+    world: Rc<RefCell<World>>,
+    index: usize,
+}
+
+impl SimSensor {
+    fn with<R>(&self, f: impl FnOnce(&mut Sensor) -> R) -> R {
+        f(&mut self.world.borrow_mut().sensors[self.index])
+    }
+
+    /// X shutdown pin of this sensor implementing [embedded_hal::digital::OutputPin].
+    pub fn x_shutdown_pin(&self) -> SimXShutdownPin {
+        SimXShutdownPin {
+            sensor: self.clone(),
+        }
+    }
+
+    /// GPIO1 interrupt output of this sensor implementing [embedded_hal::digital::InputPin].
+    pub fn interrupt_pin(&self) -> SimInterruptPin {
+        SimInterruptPin {
+            sensor: self.clone(),
+            gpio: 1,
+        }
+    }
+
+    /// GPIO0 of this sensor as interrupt output, see
+    /// [set_gpio0_function](crate::Config::set_gpio0_function).
+    pub fn gpio0_interrupt_pin(&self) -> SimInterruptPin {
+        SimInterruptPin {
+            sensor: self.clone(),
+            gpio: 0,
+        }
+    }
+
+    /// Whether the sensor is powered on.
+    pub fn is_powered(&self) -> bool {
+        self.with(|s| s.powered)
+    }
+
+    /// Current I2C address of the sensor.
+    pub fn address(&self) -> u8 {
+        self.with(|s| s.address())
+    }
+
+    /// Time between the x shutdown pin going high and the sensor answering on the bus. Default = 0
+    pub fn set_boot_time_us(&self, us: u64) {
+        self.with(|s| s.boot_time_us = us);
+    }
+
+    /// Time it takes for a measurement to complete. Default = 0
+    pub fn set_measurement_time_us(&self, us: u64) {
+        self.with(|s| s.measurement_time_us = us);
+    }
+
+    /// Distance to the simulated target. Default = 100mm
+    pub fn set_range_mm(&self, range_mm: u16) {
+        self.with(|s| s.range_mm = range_mm);
+    }
+
+    /// Systematic error of this part, added to the range before the part-to-part offset
+    /// is applied. Default = 0mm
+    pub fn set_range_error_mm(&self, error_mm: i8) {
+        self.with(|s| s.range_error_mm = error_mm);
+    }
+
+    /// Return rate from the target, in 9.7 fixed point Mcps. Default = 10 Mcps
+    pub fn set_signal_rate(&self, rate: u16) {
+        self.with(|s| s.signal_rate = rate);
+    }
+
+    /// Return rate from reflections on a cover window, in 9.7 fixed point Mcps. Default = 0
+    ///
+    /// Uncompensated crosstalk makes the range read short.
+    pub fn set_crosstalk_rate(&self, rate: u16) {
+        self.with(|s| s.crosstalk_rate = rate);
+    }
+
+    /// Force the status of the following range measurements, `None` derives it from the range.
+    pub fn set_range_status(&self, status: Option<RangeStatusErrorCode>) {
+        self.with(|s| s.range_status = status);
+    }
+
+    /// Raw ALS count returned by the following ambient light measurements. Default = 0
+    pub fn set_ambient_counts(&self, counts: u16) {
+        self.with(|s| {
+            s.ambient_counts = counts;
+            s.ambient_lux = None;
+        });
+    }
+
+    /// Ambient light level of the scene in lux, replacing the raw ALS count.
+    ///
+    /// The count then follows the analogue gain and integration period, and the
+    /// measurement overflows when it doesn't fit in 16 bits.
+    pub fn set_ambient_lux(&self, lux: f32) {
+        self.with(|s| s.ambient_lux = Some(lux));
+    }
+
+    /// Force the status of the following ambient light measurements, `None` is no error.
+    pub fn set_ambient_status(&self, status: Option<AmbientStatusErrorCode>) {
+        self.with(|s| s.ambient_status = status);
+    }
+
+    /// Read an 8-bit register without going through the bus.
+    pub fn register(&self, reg: u16) -> u8 {
+        self.with(|s| s.get8(reg))
+    }
+
+    /// Read a 16-bit register without going through the bus.
+    pub fn register_16bit(&self, reg: u16) -> u16 {
+        self.with(|s| s.get16(reg))
+    }
+
+    /// Set an 8-bit register without going through the bus, e.g. to load factory calibration.
+    pub fn set_register(&self, reg: u16, value: u8) {
+        self.with(|s| s.set8(reg, value));
+    }
+
+    /// Set a 16-bit register without going through the bus.
+    pub fn set_register_16bit(&self, reg: u16, value: u16) {
+        self.with(|s| s.set16(reg, value));
+    }
+}
+
+/// Simulated I2C bus, see [SimBus::i2c].
+#[derive(Debug, Clone)]
+pub struct SimI2c {
+    world: Rc<RefCell<World>>,
+}
+
+impl i2c::ErrorType for SimI2c {
+    type Error = ErrorKind;
+}
+
+impl i2c::I2c for SimI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut world = self.world.borrow_mut();
+        let transaction_time_us = world.transaction_time_us;
+        world.advance(transaction_time_us);
+        let now = world.now_us;
+        let sensor = world
+            .sensors
+            .iter_mut()
+            .find(|s| s.responds(address, now))
+            .ok_or(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))?;
+
+        // The first two bytes written set the register index, following bytes are data
+        let mut index_bytes = 0;
+        let mut index = [0u8; 2];
+        for operation in operations.iter_mut() {
+            match operation {
+                Operation::Write(bytes) => {
+                    for byte in bytes.iter() {
+                        if index_bytes < 2 {
+                            index[index_bytes] = *byte;
+                            index_bytes += 1;
+                            if index_bytes == 2 {
+                                sensor.pointer = u16::from_be_bytes(index);
+                            }
+                        } else {
+                            sensor.write(*byte, now);
+                        }
+                    }
+                }
+                Operation::Read(buffer) => {
+                    index_bytes = 0;
+                    for byte in buffer.iter_mut() {
+                        *byte = sensor.read();
+                    }
+                }
+            }
+        }
+        sensor.update(now);
+        Ok(())
+    }
+}
+
+/// Simulated delay advancing the shared clock, see [SimBus::delay].
+#[derive(Debug, Clone)]
+pub struct SimDelay {
+    world: Rc<RefCell<World>>,
+}
+
+impl DelayNs for SimDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.world
+            .borrow_mut()
+            .advance(ns as u64 / 1000 + u64::from(ns % 1000 != 0));
+    }
+}
+
+/// Simulated x shutdown pin, see [SimSensor::x_shutdown_pin].
+#[derive(Debug, Clone)]
+pub struct SimXShutdownPin {
+    sensor: SimSensor,
+}
+
+impl digital::ErrorType for SimXShutdownPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for SimXShutdownPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let now = self.sensor.world.borrow().now_us;
+        self.sensor.with(|s| s.power(false, now));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let now = self.sensor.world.borrow().now_us;
+        self.sensor.with(|s| s.power(true, now));
+        Ok(())
+    }
+}
+
+/// Simulated GPIO interrupt output, see [SimSensor::interrupt_pin] and
+/// [SimSensor::gpio0_interrupt_pin].
+///
+/// Pending measurements are completed when they are due while the pin is read. Waiting
+/// for the pin with `embedded_hal_async::digital::Wait` yields until the pin changes, as the
+/// simulated clock only advances with the bus and the [SimDelay].
+#[derive(Debug, Clone)]
+pub struct SimInterruptPin {
+    sensor: SimSensor,
+    gpio: u8,
+}
+
+#[cfg(feature = "async")]
+impl SimInterruptPin {
+    /// Yields until the pin is at `high`, the simulated clock is advanced by the other
+    /// futures, e.g. a [SimDelay] raced against the pin.
+    async fn wait_for_level(&mut self, high: bool) -> Result<(), Infallible> {
+        core::future::poll_fn(|cx| match self.is_high() {
+            Ok(level) if level != high => {
+                cx.waker().wake_by_ref();
+                core::task::Poll::Pending
+            }
+            result => core::task::Poll::Ready(result.map(|_| ())),
+        })
+        .await
+    }
+}
+
+impl digital::ErrorType for SimInterruptPin {
+    type Error = Infallible;
+}
+
+impl InputPin for SimInterruptPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        let mut world = self.sensor.world.borrow_mut();
+        let now = world.now_us;
+        let sensor = &mut world.sensors[self.sensor.index];
+        sensor.update(now);
+        Ok(sensor.gpio_is_high(self.gpio))
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_high()?)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::i2c::I2c for SimI2c {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        i2c::I2c::transaction(self, address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for SimDelay {
+    async fn delay_ns(&mut self, ns: u32) {
+        DelayNs::delay_ns(self, ns)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::digital::Wait for SimInterruptPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(true).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_level(false).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_low().await?;
+        self.wait_for_high().await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait_for_high().await?;
+        self.wait_for_low().await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        if self.is_high()? {
+            self.wait_for_low().await
+        } else {
+            self.wait_for_high().await
+        }
+    }
+}
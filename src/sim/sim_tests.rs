@@ -0,0 +1,385 @@
+use super::*;
+use crate::{
+    read_measurements::range_read_timeout_us, register::Register8Bit::*, Config, DelayWait, Error,
+    Error2, Gpio0Function, Gpio1Function, GpioPolarity, OperatingMode, RangeInterruptMode,
+    VL6180XwPins, VL6180X,
+};
+use embedded_hal::i2c::I2c;
+
+#[test]
+fn new_initializes_sensor() {
+    let (bus, sensor) = SimBus::with_sensor();
+    VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(sensor.register(SYSTEM__FRESH_OUT_OF_RESET as u16), 0);
+    assert_eq!(sensor.register(SYSTEM__INTERRUPT_CONFIG_GPIO as u16), 0x24);
+    assert_eq!(sensor.register(SYSTEM__MODE_GPIO1 as u16), 0x30);
+    assert_eq!(sensor.register(SYSRANGE__MAX_CONVERGENCE_TIME as u16), 49);
+}
+
+#[test]
+fn new_rejects_unknown_model_id() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_register(IDENTIFICATION__MODEL_ID as u16, 0xAB);
+    assert_eq!(
+        VL6180X::new(bus.i2c()).err().unwrap(),
+        Error::InvalidDevice(0xAB)
+    );
+}
+
+#[test]
+fn new_without_sensor_is_bus_error() {
+    let bus = SimBus::new();
+    assert_eq!(
+        VL6180X::new(bus.i2c()).err().unwrap(),
+        Error::BusError(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+    );
+}
+
+#[test]
+fn poll_range_single() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(87);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(87));
+}
+
+#[test]
+fn poll_range_single_with_scaling() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(451);
+    let mut config = Config::new();
+    config.set_range_result_scaler(3).unwrap();
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(450));
+}
+
+#[test]
+fn poll_range_single_status_error() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_status(Some(RangeStatusErrorCode::MaxConvergence));
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(
+        tof.poll_range_mm_single_blocking(),
+        Err(Error::RangeStatusError(
+            RangeStatusErrorCode::MaxConvergence
+        ))
+    );
+}
+
+#[test]
+fn poll_range_single_times_out() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(100_000);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Err(Error::Timeout));
+}
+
+#[test]
+fn poll_range_single_waits_for_slow_measurement() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(50_000);
+    bus.set_transaction_time_us(50);
+    let wait = DelayWait::new(bus.delay());
+    let mut tof = VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), wait).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(100));
+}
+
+#[test]
+fn read_range_not_ready() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(10_000);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    tof.start_range_single().unwrap();
+    assert_eq!(tof.read_range_mm(), Err(Error::ResultNotReady));
+    bus.advance_us(10_000);
+    assert_eq!(tof.read_range_mm(), Ok(100));
+}
+
+#[test]
+fn poll_ambient_single() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_counts(1010);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    let lux = tof.poll_ambient_lux_single_blocking().unwrap();
+    assert!((lux - 320.0).abs() < 0.01);
+}
+
+#[test]
+fn range_continuous() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut tof = tof.start_range_continuous_mode().unwrap();
+    sensor.set_range_mm(50);
+    assert_eq!(tof.read_range_mm_blocking(), Ok(50));
+    sensor.set_range_mm(60);
+    assert_eq!(tof.read_range_mm_blocking(), Ok(60));
+    let mut tof = tof.stop_range_continuous_mode().unwrap();
+    tof.clear_all_interrupts().unwrap();
+    assert_eq!(tof.read_range_mm(), Err(Error::ResultNotReady));
+}
+
+#[test]
+fn interleaved_continuous() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_ambient_counts(1010);
+    let tof = VL6180X::new(bus.i2c()).unwrap();
+    let mut tof = tof.start_interleaved_continuous_mode().unwrap();
+    assert_eq!(tof.read_range_mm_blocking(), Ok(100));
+    assert_eq!(tof.read_ambient_blocking(), Ok(1010));
+    tof.stop_interleaved_continuous_mode().unwrap();
+}
+
+#[test]
+fn range_interrupt_thresholds() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_interrupt_mode(RangeInterruptMode::LevelLow);
+    config.set_range_low_interrupt_threshold(80);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+    tof.start_range_single().unwrap();
+    assert_eq!(tof.read_range_mm(), Err(Error::ResultNotReady));
+    sensor.set_range_mm(70);
+    tof.start_range_single().unwrap();
+    assert_eq!(tof.read_range_mm(), Ok(70));
+}
+
+#[test]
+fn grouped_parameter_hold_defers_thresholds() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_range_interrupt_mode(RangeInterruptMode::LevelLow);
+    config.set_range_low_interrupt_threshold(80);
+    let mut tof = VL6180X::with_config(bus.i2c(), &config).unwrap();
+
+    let mut i2c = bus.i2c();
+    let hold = SYSTEM__GROUPED_PARAMETER_HOLD as u16;
+    let low = SYSRANGE__THRESH_LOW as u16;
+    i2c.write(DEFAULT_ADDRESS, &[(hold >> 8) as u8, hold as u8, 1])
+        .unwrap();
+    i2c.write(DEFAULT_ADDRESS, &[(low >> 8) as u8, low as u8, 120])
+        .unwrap();
+    assert_eq!(sensor.register(low), 80);
+    tof.start_range_single().unwrap();
+    assert_eq!(tof.read_range_mm(), Err(Error::ResultNotReady));
+
+    i2c.write(DEFAULT_ADDRESS, &[(hold >> 8) as u8, hold as u8, 0])
+        .unwrap();
+    assert_eq!(sensor.register(low), 120);
+    tof.start_range_single().unwrap();
+    assert_eq!(tof.read_range_mm(), Ok(100));
+}
+
+#[test]
+fn change_i2c_address() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    tof.change_i2c_address(0x30).unwrap();
+    assert_eq!(sensor.address(), 0x30);
+    assert_eq!(tof.read_model_id(), Ok(0xB4));
+}
+
+#[test]
+fn power_cycle() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    sensor.set_range_mm(55);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(55));
+    let tof = tof.power_off(&mut x_shutdown_pin).unwrap();
+    assert!(!sensor.is_powered());
+    let mut tof = tof
+        .power_on_and_init(&mut x_shutdown_pin, &mut bus.delay())
+        .unwrap();
+    assert!(sensor.is_powered());
+    assert_eq!(sensor.register(SYSTEM__FRESH_OUT_OF_RESET as u16), 0);
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(55));
+}
+
+#[test]
+fn dynamic_mode() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut x_shutdown_pin = sensor.x_shutdown_pin();
+    let mut tof = VL6180X::new(bus.i2c()).unwrap().into_dynamic_mode();
+    tof.try_start_range_continuous_mode().unwrap();
+    assert_eq!(
+        tof.try_poll_range_mm_single_blocking(),
+        Err(Error::InvalidMethod(OperatingMode::RangeContinuous))
+    );
+    assert_eq!(tof.try_read_range_mm_blocking(), Ok(100));
+    tof.try_stop_range_continuous_mode().unwrap();
+    tof.try_power_off(&mut x_shutdown_pin).unwrap();
+    tof.try_power_on_and_init(&mut x_shutdown_pin, &mut bus.delay())
+        .unwrap();
+    assert_eq!(tof.try_poll_ambient_lux_single_blocking(), Ok(0.0));
+}
+
+#[test]
+fn interrupt_pin_measurement() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut tof = VL6180XwPins {
+        vl6180x: VL6180X::new(bus.i2c()).unwrap(),
+        x_shutdown_pin: sensor.x_shutdown_pin(),
+        interrupt_pin: sensor.interrupt_pin(),
+    };
+    sensor.set_range_mm(42);
+    assert_eq!(tof.poll_range_mm_single_interrupt_blocking(), Ok(42));
+    assert_eq!(tof.interrupt_pin.is_high(), Ok(false));
+}
+
+#[test]
+fn interrupt_pin_waits_for_requested_event() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(5_000);
+    sensor.set_range_mm(42);
+    sensor.set_ambient_counts(500);
+    let wait = DelayWait::new(bus.delay());
+    let mut tof = VL6180XwPins {
+        vl6180x: VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), wait).unwrap(),
+        x_shutdown_pin: sensor.x_shutdown_pin(),
+        interrupt_pin: sensor.interrupt_pin(),
+    };
+    // The unread range result keeps the interrupt line asserted
+    tof.vl6180x.start_range_single().unwrap();
+    bus.delay().delay_us(10_000);
+    assert_eq!(tof.interrupt_pin.is_high(), Ok(true));
+
+    let start_us = bus.now_us();
+    let lux = tof.poll_ambient_lux_single_interrupt_blocking().unwrap();
+    assert!((lux - 158.4).abs() < 0.1);
+    assert!(bus.now_us() - start_us >= 5_000);
+    assert_eq!(tof.read_range_mm_interrupt_blocking(), Ok(42));
+}
+
+#[test]
+fn interrupt_pin_on_gpio0_active_low() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_gpio0_function(Gpio0Function::InterruptOutput);
+    config.set_gpio0_polarity(GpioPolarity::ActiveLow);
+    config.set_gpio1_function(Gpio1Function::Off);
+    let mut tof = VL6180XwPins {
+        vl6180x: VL6180X::with_config(bus.i2c(), &config).unwrap(),
+        x_shutdown_pin: sensor.x_shutdown_pin(),
+        interrupt_pin: sensor.gpio0_interrupt_pin(),
+    };
+    assert_eq!(sensor.register(SYSTEM__MODE_GPIO0 as u16), 0x10);
+    assert_eq!(sensor.register(SYSTEM__MODE_GPIO1 as u16), 0x20);
+    assert_eq!(tof.interrupt_pin.is_high(), Ok(true));
+    sensor.set_range_mm(42);
+    assert_eq!(tof.poll_range_mm_single_interrupt_blocking(), Ok(42));
+    assert_eq!(tof.interrupt_pin.is_high(), Ok(true));
+}
+
+#[test]
+fn interrupt_pin_without_interrupt_output() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let mut config = Config::new();
+    config.set_gpio1_function(Gpio1Function::Off);
+    let mut tof = VL6180XwPins {
+        vl6180x: VL6180X::with_config(bus.i2c(), &config).unwrap(),
+        x_shutdown_pin: sensor.x_shutdown_pin(),
+        interrupt_pin: sensor.interrupt_pin(),
+    };
+    assert_eq!(
+        tof.poll_range_mm_single_interrupt_blocking(),
+        Err(Error2::DriverError(Error::NoInterruptOutput))
+    );
+}
+
+#[test]
+fn interrupt_pin_never_asserted_times_out() {
+    let (bus, sensor) = SimBus::with_sensor();
+    // Reading the pin takes no time on the bus.
+    let mut wait = DelayWait::new(bus.delay());
+    wait.set_poll_time_us(0);
+    let mut tof = VL6180XwPins {
+        vl6180x: VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), wait).unwrap(),
+        x_shutdown_pin: sensor.x_shutdown_pin(),
+        interrupt_pin: sensor.interrupt_pin(),
+    };
+    // No measurement is started, so the pin stays deasserted.
+    let start_us = bus.now_us();
+    assert_eq!(
+        tof.read_range_mm_interrupt_blocking(),
+        Err(Error2::DriverError(Error::Timeout))
+    );
+    let waited_us = bus.now_us() - start_us;
+    let timeout_us = range_read_timeout_us(&Config::new(), false) as u64;
+    assert!(waited_us >= timeout_us);
+    assert!(waited_us < timeout_us + 1_000);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_interrupt_pin_active_low() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(5_000);
+    let mut config = Config::new();
+    config.set_gpio1_polarity(GpioPolarity::ActiveLow);
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180XwPins {
+            vl6180x: crate::asynch::VL6180X::with_config(bus.i2c(), bus.delay(), &config)
+                .await
+                .unwrap(),
+            x_shutdown_pin: sensor.x_shutdown_pin(),
+            interrupt_pin: sensor.interrupt_pin(),
+        };
+        assert_eq!(tof.poll_range_mm_single_interrupt().await, Ok(100));
+    });
+    assert!(bus.now_us() >= 5_000);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_poll_range_single() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(33);
+    sensor.set_measurement_time_us(5_000);
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+            .await
+            .unwrap();
+        assert_eq!(tof.poll_range_mm_single().await, Ok(33));
+    });
+    assert!(bus.now_us() >= 5_000);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_interrupt_pin_measurement() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(5_000);
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180XwPins {
+            vl6180x: crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+                .await
+                .unwrap(),
+            x_shutdown_pin: sensor.x_shutdown_pin(),
+            interrupt_pin: sensor.interrupt_pin(),
+        };
+        assert_eq!(tof.poll_range_mm_single_interrupt().await, Ok(100));
+    });
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_interrupt_pin_never_asserted_times_out() {
+    let (bus, sensor) = SimBus::with_sensor();
+    embassy_futures::block_on(async {
+        let mut tof = crate::asynch::VL6180XwPins {
+            vl6180x: crate::asynch::VL6180X::new(bus.i2c(), bus.delay())
+                .await
+                .unwrap(),
+            x_shutdown_pin: sensor.x_shutdown_pin(),
+            interrupt_pin: sensor.interrupt_pin(),
+        };
+        let start_us = bus.now_us();
+        assert_eq!(
+            tof.read_ambient_lux_interrupt().await,
+            Err(Error2::DriverError(Error::Timeout))
+        );
+        let timeout_us =
+            crate::read_measurements::ambient_read_timeout_us(&Config::new(), false) as u64;
+        assert!(bus.now_us() - start_us >= timeout_us);
+    });
+}
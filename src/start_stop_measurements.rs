@@ -1,28 +1,86 @@
 use crate::{
     error::Error,
-    register::{InterleavedModeEnableCode, Register8Bit, SysAmbientStartCode, SysRangeStartCode},
-    VL6180X,
+    read_measurements::{
+        ambient_measurement, ambient_timeout_us, auto_gain_level, auto_range_scaling,
+        convert_raw_ambient_to_lux, range_timeout_us, AmbientMeasurement, ScaledRangeMeasurement,
+        AUTO_GAIN_ATTEMPTS, AUTO_SCALING_ATTEMPTS,
+    },
+    register::{
+        InterleavedModeEnableCode, Register8Bit, ResultInterruptStatusGpioCode,
+        SysAmbientStartCode, SysRangeStartCode,
+    },
+    Config, RangeMeasurement, WaitStrategy, VL6180X,
 };
-use embedded_hal::blocking::i2c::{Write, WriteRead};
+use embedded_hal::i2c::I2c;
 
-impl<MODE, I2C, E> VL6180X<MODE, I2C>
+impl<MODE, I2C, E, W> VL6180X<MODE, I2C, W>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E>,
+    I2C: I2c<Error = E>,
+    W: WaitStrategy,
 {
     pub(crate) fn poll_range_mm_single_blocking_direct(&mut self) -> Result<u16, Error<E>> {
-        self.write_named_register(
-            Register8Bit::SYSRANGE__START,
-            SysRangeStartCode::SingleStart as u8,
-        )?;
-        self.read_range_mm_blocking_direct()
+        self.start_range_single_direct()?;
+        let timeout_us = range_timeout_us(&self.config);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)?;
+        self.get_range_val_and_status()
+    }
+
+    pub(crate) fn poll_range_mm_single_auto_scaling_blocking_direct(
+        &mut self,
+    ) -> Result<ScaledRangeMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.poll_range_mm_single_blocking_direct();
+            match auto_range_scaling(&self.config, &result) {
+                Some(scaling) if attempts < AUTO_SCALING_ATTEMPTS => {
+                    self.set_range_scaling_direct(scaling)?;
+                    attempts += 1;
+                }
+                _ => {
+                    return Ok(ScaledRangeMeasurement {
+                        range_mm: result?,
+                        scaling: self.config.range_scaling,
+                    })
+                }
+            }
+        }
+    }
+
+    pub(crate) fn poll_range_detailed_single_blocking_direct(
+        &mut self,
+    ) -> Result<RangeMeasurement, Error<E>> {
+        self.start_range_single_direct()?;
+        let timeout_us = range_timeout_us(&self.config);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoRangeEvents, timeout_us)?;
+        self.get_range_detailed()
     }
 
     pub(crate) fn poll_ambient_lux_single_blocking_direct(&mut self) -> Result<f32, Error<E>> {
-        self.write_named_register(
-            Register8Bit::SYSALS__START,
-            SysAmbientStartCode::SingleStart as u8,
-        )?;
-        self.read_ambient_lux_blocking_direct()
+        let raw_ambient = self.poll_ambient_single_blocking_direct()?;
+        Ok(convert_raw_ambient_to_lux(&self.config, raw_ambient))
+    }
+
+    pub(crate) fn poll_ambient_lux_single_auto_gain_blocking_direct(
+        &mut self,
+    ) -> Result<AmbientMeasurement, Error<E>> {
+        let mut attempts = 1;
+        loop {
+            let result = self.poll_ambient_single_blocking_direct();
+            match auto_gain_level(self.config.ambient_analogue_gain_level, &result) {
+                Some(level) if attempts < AUTO_GAIN_ATTEMPTS => {
+                    self.set_ambient_gain_level_direct(level)?;
+                    attempts += 1;
+                }
+                _ => return Ok(ambient_measurement(&self.config, result?)),
+            }
+        }
+    }
+
+    pub(crate) fn poll_ambient_single_blocking_direct(&mut self) -> Result<u16, Error<E>> {
+        self.start_ambient_single_direct()?;
+        let timeout_us = ambient_timeout_us(&self.config);
+        self.wait_for_event(ResultInterruptStatusGpioCode::NoAmbientEvents, timeout_us)?;
+        self.get_ambient_val_and_status()
     }
 
     pub(crate) fn start_ambient_single_direct(&mut self) -> Result<(), E> {
@@ -55,7 +113,7 @@ where
 
     /// Enables continuous interleaved measurement.
     pub(crate) fn enable_interleaved_continuous_direct(&mut self) -> Result<(), Error<E>> {
-        self.check_config_valid()?;
+        check_interleaved_config_valid(&self.config)?;
 
         self.write_named_register(
             Register8Bit::INTERLEAVED_MODE__ENABLE,
@@ -68,25 +126,6 @@ where
         Ok(())
     }
 
-    /// For interleaved mode, the following equation must be satisfied:
-    ///
-    /// ([range_max_convergence_time](Config::set_range_max_convergence_time) + 5) +
-    /// ([ambient_integration_period](Config::set_ambient_integration_period) * 1.1)
-    /// ≤ `ambient_inter_measurement_period` * 0.9
-    ///
-    /// The interleaved requirement is only checked when the interleaved mode is started.
-    fn check_config_valid(&self) -> Result<(), Error<E>> {
-        let min_eq_val = (((self.config.range_max_convergence_time + 5) as f32
-            + self.config.ambient_integration_period as f32 * 1.1)
-            / 0.9) as u16;
-        if self.config.ambient_inter_measurement_period < min_eq_val {
-            return Err(Error::InvalidConfigurationValue(
-                self.config.ambient_inter_measurement_period,
-            ));
-        }
-        Ok(())
-    }
-
     /// Stops interleaved continuous mode.
     pub fn stop_interleaved_continuous_direct(&mut self) -> Result<(), E> {
         self.write_named_register(
@@ -95,3 +134,22 @@ where
         )
     }
 }
+
+/// For interleaved mode, the following equation must be satisfied:
+///
+/// ([range_max_convergence_time](Config::set_range_max_convergence_time) + 5) +
+/// ([ambient_integration_period](Config::set_ambient_integration_period) * 1.1)
+/// ≤ `ambient_inter_measurement_period` * 0.9
+///
+/// The interleaved requirement is only checked when the interleaved mode is started.
+pub(crate) fn check_interleaved_config_valid<E>(config: &Config) -> Result<(), Error<E>> {
+    let min_eq_val = (((config.range_max_convergence_time + 5) as f32
+        + config.ambient_integration_period as f32 * 1.1)
+        / 0.9) as u16;
+    if config.ambient_inter_measurement_period < min_eq_val {
+        return Err(Error::InvalidConfigurationValue(
+            config.ambient_inter_measurement_period,
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use crate::GpioPolarity;
+use embedded_hal::{delay::DelayNs, digital::InputPin};
+
+#[cfg(test)]
+mod wait_tests;
+
+/// How the blocking driver waits for a measurement result before polling
+/// `RESULT__INTERRUPT_STATUS_GPIO` again, see
+/// [with_wait_strategy](crate::VL6180X::with_wait_strategy).
+///
+/// Trades bus traffic and power draw against latency. The measurement timeouts are
+/// based on the time reported by the strategy.
+pub trait WaitStrategy {
+    /// Waits before the next poll of the interrupt status, for at most `remaining_us`
+    /// which is the time left until the measurement times out.
+    ///
+    /// Returns the time (us) that passed while waiting, including the time of the poll.
+    fn wait(&mut self, remaining_us: u32) -> u32;
+}
+
+impl<W: WaitStrategy + ?Sized> WaitStrategy for &mut W {
+    fn wait(&mut self, remaining_us: u32) -> u32 {
+        (**self).wait(remaining_us)
+    }
+}
+
+/// Polls the interrupt status back-to-back without waiting in between.
+///
+/// Gives the lowest latency at the cost of continuous bus traffic. As there is no time
+/// source, each poll is assumed to take `poll_time_us`, so the measurement timeouts are
+/// poll counts rather than microseconds: a timeout of `t` us expires after
+/// `t / poll_time_us` polls, however long they really take. `poll_time_us` should be the
+/// duration of a register read on the bus. The default of 50us is the duration on a
+/// 1 MHz bus, so on slower buses the timeouts expire late rather than early.
+///
+/// This is the wait strategy of [VL6180X::new](crate::VL6180X::new). Use [DelayWait], the
+/// wait strategy of [VL6180X::new_with_delay](crate::VL6180X::new_with_delay), for timeouts
+/// in real time.
+#[derive(Debug, Clone, Copy)]
+pub struct BusyWait {
+    poll_time_us: u32,
+}
+
+impl BusyWait {
+    /// Create a busy wait strategy assuming each poll takes `poll_time_us`.
+    pub fn new(poll_time_us: u32) -> Self {
+        Self {
+            poll_time_us: poll_time_us.max(1),
+        }
+    }
+}
+
+impl Default for BusyWait {
+    fn default() -> Self {
+        Self::new(50)
+    }
+}
+
+impl WaitStrategy for BusyWait {
+    fn wait(&mut self, _remaining_us: u32) -> u32 {
+        self.poll_time_us
+    }
+}
+
+/// Waits `interval_us` with an embedded-hal delay between two polls of the interrupt status.
+///
+/// The measurement timeouts count the delays plus `poll_time_us` for each poll, the
+/// duration of a register read on the bus, see [set_poll_time_us](DelayWait::set_poll_time_us).
+#[derive(Debug, Clone, Copy)]
+pub struct DelayWait<D> {
+    delay: D,
+    interval_us: u32,
+    poll_time_us: u32,
+}
+
+impl<D: DelayNs> DelayWait<D> {
+    /// Create a delay wait strategy polling every 100us.
+    pub fn new(delay: D) -> Self {
+        Self::with_interval_us(delay, 100)
+    }
+
+    /// Create a delay wait strategy polling every `interval_us`.
+    pub fn with_interval_us(delay: D, interval_us: u32) -> Self {
+        Self {
+            delay,
+            interval_us: interval_us.max(1),
+            poll_time_us: 50,
+        }
+    }
+
+    /// Set the duration of a poll of the interrupt status, a register read on the bus.
+    ///
+    /// Default = 50us, the duration on a 1 MHz bus. On slower buses the timeouts expire
+    /// late rather than early unless it is raised.
+    pub fn set_poll_time_us(&mut self, poll_time_us: u32) {
+        self.poll_time_us = poll_time_us;
+    }
+
+    /// Release the delay.
+    pub fn release(self) -> D {
+        self.delay
+    }
+}
+
+impl<D: DelayNs> WaitStrategy for DelayWait<D> {
+    fn wait(&mut self, remaining_us: u32) -> u32 {
+        let interval_us = self
+            .interval_us
+            .min(remaining_us.saturating_sub(self.poll_time_us).max(1));
+        self.delay.delay_us(interval_us);
+        interval_us + self.poll_time_us
+    }
+}
+
+/// Waits for the sensor's GPIO1 interrupt output, checking the pin every `interval_us`,
+/// so the bus is only used once the interrupt is asserted.
+///
+/// The pin is assumed to be active high as configured by default, see
+/// [set_polarity](InterruptWait::set_polarity). The interrupt status is still
+/// read over I2C once the pin is asserted, so an interrupt of another measurement, or a pin
+/// error, only costs an extra poll.
+#[derive(Debug, Clone, Copy)]
+pub struct InterruptWait<P, D> {
+    interrupt_pin: P,
+    delay: D,
+    interval_us: u32,
+    polarity: GpioPolarity,
+}
+
+impl<P: InputPin, D: DelayNs> InterruptWait<P, D> {
+    /// Create an interrupt wait strategy checking the pin every 100us.
+    pub fn new(interrupt_pin: P, delay: D) -> Self {
+        Self::with_interval_us(interrupt_pin, delay, 100)
+    }
+
+    /// Create an interrupt wait strategy checking the pin every `interval_us`.
+    pub fn with_interval_us(interrupt_pin: P, delay: D, interval_us: u32) -> Self {
+        Self {
+            interrupt_pin,
+            delay,
+            interval_us: interval_us.max(1),
+            polarity: GpioPolarity::ActiveHigh,
+        }
+    }
+
+    /// Set the polarity of the interrupt pin, which has to match the
+    /// [GPIO configuration](crate::Config::set_gpio1_polarity) of the sensor.
+    pub fn set_polarity(&mut self, polarity: GpioPolarity) {
+        self.polarity = polarity;
+    }
+
+    /// Release the interrupt pin and the delay.
+    pub fn release(self) -> (P, D) {
+        (self.interrupt_pin, self.delay)
+    }
+}
+
+impl<P: InputPin, D: DelayNs> WaitStrategy for InterruptWait<P, D> {
+    fn wait(&mut self, remaining_us: u32) -> u32 {
+        let mut waited_us: u32 = 0;
+        loop {
+            self.delay.delay_us(self.interval_us);
+            waited_us = waited_us.saturating_add(self.interval_us);
+            let asserted = match self.interrupt_pin.is_high() {
+                Ok(high) => high == (self.polarity == GpioPolarity::ActiveHigh),
+                Err(_) => true,
+            };
+            if waited_us >= remaining_us || asserted {
+                return waited_us;
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+use crate::read_measurements::range_timeout_us;
+use crate::sim::SimBus;
+use crate::{Config, Error, VL6180X};
+
+#[test]
+fn busy_wait_reports_poll_time() {
+    assert_eq!(BusyWait::default().wait(10_000), 50);
+    assert_eq!(BusyWait::new(0).wait(10_000), 1);
+}
+
+#[test]
+fn busy_wait_times_out_without_a_clock() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(1_000);
+    let mut tof =
+        VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), BusyWait::default()).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Err(Error::Timeout));
+    assert_eq!(bus.now_us(), 0);
+}
+
+#[test]
+fn delay_wait_stops_at_remaining_time() {
+    let bus = SimBus::new();
+    let mut wait = DelayWait::with_interval_us(bus.delay(), 500);
+    assert_eq!(wait.wait(10_000), 550);
+    assert_eq!(wait.wait(200), 200);
+    assert_eq!(bus.now_us(), 650);
+}
+
+#[test]
+fn timeouts_with_slow_polls() {
+    let (bus, sensor) = SimBus::with_sensor();
+    let timeout_us = range_timeout_us(&Config::new());
+    sensor.set_measurement_time_us(100 * timeout_us as u64);
+    bus.set_transaction_time_us(1_000);
+
+    // Counting the poll time, the delay wait times out after the timeout in real time.
+    let mut wait = DelayWait::new(bus.delay());
+    wait.set_poll_time_us(1_000);
+    let mut tof = VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), wait).unwrap();
+    let start_us = bus.now_us();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Err(Error::Timeout));
+    let elapsed_us = bus.now_us() - start_us;
+    assert!(elapsed_us >= timeout_us as u64);
+    assert!(elapsed_us <= timeout_us as u64 + 3_000);
+
+    // The busy wait counts polls of 50us, so it times out 20 times later.
+    let mut tof =
+        VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), BusyWait::default()).unwrap();
+    let start_us = bus.now_us();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Err(Error::Timeout));
+    assert!(bus.now_us() - start_us >= 20 * timeout_us as u64);
+}
+
+#[test]
+fn interrupt_wait_returns_once_pin_is_asserted() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_measurement_time_us(1_000);
+    let mut tof = VL6180X::new(bus.i2c()).unwrap();
+    tof.start_range_single().unwrap();
+    let mut wait = InterruptWait::with_interval_us(sensor.interrupt_pin(), bus.delay(), 100);
+    assert_eq!(wait.wait(300), 300);
+    assert_eq!(wait.wait(10_000), 700);
+    assert_eq!(tof.read_range_mm(), Ok(100));
+}
+
+#[test]
+fn interrupt_wait_active_low() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(42);
+    sensor.set_measurement_time_us(5_000);
+    let mut config = Config::new();
+    config.set_gpio1_polarity(GpioPolarity::ActiveLow);
+    let mut wait = InterruptWait::new(sensor.interrupt_pin(), bus.delay());
+    wait.set_polarity(GpioPolarity::ActiveLow);
+    let mut tof = VL6180X::with_wait_strategy(bus.i2c(), &config, wait).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(42));
+    assert_eq!(bus.now_us(), 5_000);
+}
+
+#[test]
+fn poll_with_interrupt_wait() {
+    let (bus, sensor) = SimBus::with_sensor();
+    sensor.set_range_mm(42);
+    sensor.set_measurement_time_us(5_000);
+    let wait = InterruptWait::new(sensor.interrupt_pin(), bus.delay());
+    let mut tof = VL6180X::with_wait_strategy(bus.i2c(), &Config::new(), wait).unwrap();
+    assert_eq!(tof.poll_range_mm_single_blocking(), Ok(42));
+    assert_eq!(bus.now_us(), 5_000);
+}